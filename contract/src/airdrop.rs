@@ -0,0 +1,180 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, bytesrepr::ToBytes, CLType, CLTyped, CLValue, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CLAIM, DEADLINE, DISTRIBUTIONS, DISTRIBUTION_CLAIMS, DISTRIBUTION_COUNT,
+        DISTRIBUTION_ID, DISTRIBUTION_PURSE, FUND_DISTRIBUTION, MERKLE_ROOT, PROOF,
+        RECLAIM_DISTRIBUTION,
+    },
+    credit_deposit,
+    error::Error,
+    spend_deposit,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, named_purse, next_id, require_owner,
+        set_dictionary_value,
+    },
+};
+
+// Prefixes that keep leaf hashes and inner node hashes apart, so an inner node cannot pass for a leaf.
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+// Distributions pay out rewards to many accounts without a deposit per account. The owner funds
+// a distribution with the Merkle root of its (account hash, amount) leaves, and every account in
+// the tree claims its own amount with a proof, which is credited to its deposit exactly once.
+// Claims close at the `deadline` of the distribution, after which the funder can reclaim what is left.
+// Leaves are the blake2b hash of `LEAF_PREFIX` followed by the serialized pair, and every inner node is the
+// blake2b hash of `NODE_PREFIX` followed by its two children in ascending order, so a proof is just the list
+// of sibling hashes from leaf to root.
+struct Distribution {
+    funder: AccountHash,
+    merkle_root: [u8; 32],
+    remaining: U512,
+    deadline: u64,
+}
+
+stored_struct!(Distribution {
+    funder,
+    merkle_root,
+    remaining,
+    deadline,
+});
+
+fn read_distribution(distribution_id: u64) -> Distribution {
+    get_dictionary_value(DISTRIBUTIONS, &distribution_id.to_string())
+        .unwrap_or_revert_with(Error::DistributionNotFound)
+}
+
+fn write_distribution(distribution_id: u64, distribution: Distribution) {
+    set_dictionary_value(DISTRIBUTIONS, &distribution_id.to_string(), distribution);
+}
+
+// Hashes a leaf up to the root along `proof`.
+fn root_from_proof(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+    proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        runtime::blake2b([&[NODE_PREFIX][..], &left[..], &right[..]].concat())
+    })
+}
+
+// Owner only. Funds a distribution with `amount` out of the deposit of the owner, claimable until
+// the block time `deadline`, and returns its id.
+#[no_mangle]
+pub extern "C" fn fund_distribution() {
+    require_owner();
+    let merkle_root: [u8; 32] = runtime::get_named_arg(MERKLE_ROOT);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let deadline: u64 = runtime::get_named_arg(DEADLINE);
+    let funder = runtime::get_caller();
+    spend_deposit(funder, named_purse(DISTRIBUTION_PURSE), amount);
+    let distribution_id = next_id(DISTRIBUTION_COUNT);
+    write_distribution(
+        distribution_id,
+        Distribution {
+            funder,
+            merkle_root,
+            remaining: amount,
+            deadline,
+        },
+    );
+    runtime::ret(CLValue::from_t(distribution_id).unwrap_or_revert());
+}
+
+// Credits `amount` to the deposit of the caller, if `proof` shows the (caller, amount) leaf is part of the distribution.
+#[no_mangle]
+pub extern "C" fn claim() {
+    let distribution_id: u64 = runtime::get_named_arg(DISTRIBUTION_ID);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let proof: Vec<[u8; 32]> = runtime::get_named_arg(PROOF);
+    let account = runtime::get_caller();
+    let mut distribution = read_distribution(distribution_id);
+    if current_time() >= distribution.deadline {
+        runtime::revert(Error::DistributionExpired);
+    }
+    let claim_key = dictionary_key(&(distribution_id, account));
+    if get_dictionary_value::<bool>(DISTRIBUTION_CLAIMS, &claim_key).unwrap_or_default() {
+        runtime::revert(Error::AlreadyClaimed);
+    }
+    let leaf = runtime::blake2b(
+        [
+            &[LEAF_PREFIX][..],
+            &(account, amount).to_bytes().unwrap_or_revert()[..],
+        ]
+        .concat(),
+    );
+    if root_from_proof(leaf, &proof) != distribution.merkle_root {
+        runtime::revert(Error::InvalidProof);
+    }
+    if amount > distribution.remaining {
+        runtime::revert(Error::InsufficientBalance);
+    }
+    credit_deposit(named_purse(DISTRIBUTION_PURSE), account, amount);
+    set_dictionary_value(DISTRIBUTION_CLAIMS, &claim_key, true);
+    distribution.remaining -= amount;
+    write_distribution(distribution_id, distribution);
+}
+
+// Funder only. Credits what nobody claimed back to the deposit of the funder, once the deadline has passed.
+#[no_mangle]
+pub extern "C" fn reclaim_distribution() {
+    let distribution_id: u64 = runtime::get_named_arg(DISTRIBUTION_ID);
+    let mut distribution = read_distribution(distribution_id);
+    if runtime::get_caller() != distribution.funder {
+        runtime::revert(Error::NotFunder);
+    }
+    if current_time() < distribution.deadline {
+        runtime::revert(Error::DistributionNotExpired);
+    }
+    if !distribution.remaining.is_zero() {
+        credit_deposit(
+            named_purse(DISTRIBUTION_PURSE),
+            distribution.funder,
+            distribution.remaining,
+        );
+    }
+    distribution.remaining = U512::zero();
+    write_distribution(distribution_id, distribution);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        FUND_DISTRIBUTION,
+        vec![
+            Parameter::new(MERKLE_ROOT, <[u8; 32]>::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(DEADLINE, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CLAIM,
+        vec![
+            Parameter::new(DISTRIBUTION_ID, u64::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(PROOF, Vec::<[u8; 32]>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        RECLAIM_DISTRIBUTION,
+        vec![Parameter::new(DISTRIBUTION_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
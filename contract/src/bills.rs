@@ -0,0 +1,218 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        BILLS, BILL_COUNT, BILL_ID, BILL_PURSE, CANCEL_BILL, COLLECT_BILL, CREATE_BILL, EXPIRES_AT,
+        PAYEE, PAY_BILL_SHARE, SHARES, WITHDRAW_BILL_SHARE,
+    },
+    credit_deposit,
+    error::Error,
+    spend_deposit,
+    utils::{
+        current_time, get_dictionary_value, has_duplicates, named_purse, next_id,
+        set_dictionary_value,
+    },
+};
+
+// Bills split one payment between participants, each paying their share out of their deposit.
+// The payee collects the whole bill into their deposit once every share is paid, even after the expiry.
+// If the payee cancels the bill, or it expires before it is fully paid, the participants who paid
+// withdraw their shares back to their deposits instead.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BillStatus {
+    Open,
+    Collected,
+    Cancelled,
+}
+
+stored_enum!(BillStatus {
+    Open,
+    Collected,
+    Cancelled,
+});
+
+struct Bill {
+    payee: AccountHash,
+    shares: Vec<(AccountHash, U512)>,
+    paid: Vec<bool>,
+    expires_at: u64,
+    status: BillStatus,
+}
+
+stored_struct!(Bill {
+    payee,
+    shares,
+    paid,
+    expires_at,
+    status,
+});
+
+impl Bill {
+    fn fully_paid(&self) -> bool {
+        self.paid.iter().all(|paid| *paid)
+    }
+
+    // The index of the share of the caller, reverting if they are not a participant.
+    fn share_of_caller(&self) -> usize {
+        let caller = runtime::get_caller();
+        self.shares
+            .iter()
+            .position(|(participant, _)| *participant == caller)
+            .unwrap_or_revert_with(Error::NotParticipant)
+    }
+}
+
+fn bill_arg() -> (u64, Bill) {
+    let bill_id: u64 = runtime::get_named_arg(BILL_ID);
+    let bill = get_dictionary_value(BILLS, &bill_id.to_string())
+        .unwrap_or_revert_with(Error::BillNotFound);
+    (bill_id, bill)
+}
+
+// Reads the bill passed in as `bill_id`, reverting unless it is open.
+fn open_bill_arg() -> (u64, Bill) {
+    let (bill_id, bill) = bill_arg();
+    if bill.status != BillStatus::Open {
+        runtime::revert(Error::BillNotOpen);
+    }
+    (bill_id, bill)
+}
+
+fn write_bill(bill_id: u64, bill: Bill) {
+    set_dictionary_value(BILLS, &bill_id.to_string(), bill);
+}
+
+// Creates a bill to `payee` split into the (participant, share) pairs of `shares`, which can be paid
+// until the block time `expires_at`, and returns its id. The deposit purse of the payee is only created
+// once they collect the bill.
+#[no_mangle]
+pub extern "C" fn create_bill() {
+    let payee: AccountHash = runtime::get_named_arg(PAYEE);
+    let shares: Vec<(AccountHash, U512)> = runtime::get_named_arg(SHARES);
+    let expires_at: u64 = runtime::get_named_arg(EXPIRES_AT);
+    let participants: Vec<AccountHash> =
+        shares.iter().map(|(participant, _)| *participant).collect();
+    if participants.is_empty()
+        || has_duplicates(&participants)
+        || shares.iter().any(|(_, share)| share.is_zero())
+    {
+        runtime::revert(Error::InvalidBill);
+    }
+    let bill_id = next_id(BILL_COUNT);
+    write_bill(
+        bill_id,
+        Bill {
+            payee,
+            paid: vec![false; shares.len()],
+            shares,
+            expires_at,
+            status: BillStatus::Open,
+        },
+    );
+    runtime::ret(CLValue::from_t(bill_id).unwrap_or_revert());
+}
+
+// Participant only. Pays the share of the caller out of their deposit.
+#[no_mangle]
+pub extern "C" fn pay_bill_share() {
+    let (bill_id, mut bill) = open_bill_arg();
+    if current_time() >= bill.expires_at {
+        runtime::revert(Error::BillExpired);
+    }
+    let index = bill.share_of_caller();
+    if bill.paid[index] {
+        runtime::revert(Error::ShareAlreadyPaid);
+    }
+    let (participant, share) = bill.shares[index];
+    spend_deposit(participant, named_purse(BILL_PURSE), share);
+    bill.paid[index] = true;
+    write_bill(bill_id, bill);
+}
+
+// Payee only. Credits the whole bill to the deposit of the payee, once every share is paid.
+#[no_mangle]
+pub extern "C" fn collect_bill() {
+    let (bill_id, mut bill) = open_bill_arg();
+    if runtime::get_caller() != bill.payee {
+        runtime::revert(Error::NotPayee);
+    }
+    if !bill.fully_paid() {
+        runtime::revert(Error::BillNotFullyPaid);
+    }
+    let total = bill
+        .shares
+        .iter()
+        .fold(U512::zero(), |total, (_, share)| total + *share);
+    credit_deposit(named_purse(BILL_PURSE), bill.payee, total);
+    bill.status = BillStatus::Collected;
+    write_bill(bill_id, bill);
+}
+
+// Payee only. Cancels an open bill, so the participants can withdraw the shares they paid.
+#[no_mangle]
+pub extern "C" fn cancel_bill() {
+    let (bill_id, mut bill) = open_bill_arg();
+    if runtime::get_caller() != bill.payee {
+        runtime::revert(Error::NotPayee);
+    }
+    bill.status = BillStatus::Cancelled;
+    write_bill(bill_id, bill);
+}
+
+// Participant only. Credits the paid share of the caller back to their deposit,
+// once the bill is cancelled, or expired without being fully paid.
+#[no_mangle]
+pub extern "C" fn withdraw_bill_share() {
+    let (bill_id, mut bill) = bill_arg();
+    let withdrawable = match bill.status {
+        BillStatus::Cancelled => true,
+        BillStatus::Open => current_time() >= bill.expires_at && !bill.fully_paid(),
+        BillStatus::Collected => false,
+    };
+    if !withdrawable {
+        runtime::revert(Error::BillNotRefundable);
+    }
+    let index = bill.share_of_caller();
+    if !bill.paid[index] {
+        runtime::revert(Error::NothingToRefund);
+    }
+    let (participant, share) = bill.shares[index];
+    credit_deposit(named_purse(BILL_PURSE), participant, share);
+    bill.paid[index] = false;
+    write_bill(bill_id, bill);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_BILL,
+        vec![
+            Parameter::new(PAYEE, AccountHash::cl_type()),
+            Parameter::new(SHARES, Vec::<(AccountHash, U512)>::cl_type()),
+            Parameter::new(EXPIRES_AT, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [
+        PAY_BILL_SHARE,
+        COLLECT_BILL,
+        CANCEL_BILL,
+        WITHDRAW_BILL_SHARE,
+    ] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![Parameter::new(BILL_ID, u64::cl_type())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+}
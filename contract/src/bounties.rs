@@ -0,0 +1,174 @@
+use alloc::{string::ToString, vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, AWARD, BOUNTIES, BOUNTY_CLAIMS, BOUNTY_COUNT, BOUNTY_ID, BOUNTY_PURSE, CLAIMANT,
+        DESCRIPTION_HASH, EVIDENCE_HASH, EXPIRY, POST_BOUNTY, REFUND_BOUNTY, SUBMIT_CLAIM,
+    },
+    credit_deposit,
+    error::Error,
+    spend_deposit,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, named_purse, next_id,
+        set_dictionary_value,
+    },
+};
+
+// Bounties lock a reward out of the deposit of the poster, for the work described by the document
+// hashed into `description_hash`. Until the expiry, hunters submit claims pointing at the hash of their
+// evidence, and the poster awards the whole reward to one of the claimants, credited to their deposit.
+// If nobody is awarded the bounty, the poster gets the reward back into their deposit after the expiry.
+struct Bounty {
+    poster: AccountHash,
+    description_hash: [u8; 32],
+    amount: U512,
+    expires_at: u64,
+    open: bool,
+}
+
+stored_struct!(Bounty {
+    poster,
+    description_hash,
+    amount,
+    expires_at,
+    open,
+});
+
+// Reads the bounty passed in as `bounty_id`, reverting if it has been awarded or refunded.
+fn bounty_arg() -> (u64, Bounty) {
+    let bounty_id: u64 = runtime::get_named_arg(BOUNTY_ID);
+    let bounty: Bounty = get_dictionary_value(BOUNTIES, &bounty_id.to_string())
+        .unwrap_or_revert_with(Error::BountyNotFound);
+    if !bounty.open {
+        runtime::revert(Error::BountyClosed);
+    }
+    (bounty_id, bounty)
+}
+
+// Closes the bounty, crediting the reward to the deposit of `recipient`. Poster only.
+fn close_bounty(bounty_id: u64, mut bounty: Bounty, recipient: AccountHash) {
+    if runtime::get_caller() != bounty.poster {
+        runtime::revert(Error::NotPoster);
+    }
+    credit_deposit(named_purse(BOUNTY_PURSE), recipient, bounty.amount);
+    bounty.open = false;
+    set_dictionary_value(BOUNTIES, &bounty_id.to_string(), bounty);
+}
+
+// Posts a bounty rewarding `amount` out of the callers deposit, open for claims until the block time `expiry`,
+// and returns its id.
+#[no_mangle]
+pub extern "C" fn post_bounty() {
+    let poster = runtime::get_caller();
+    let description_hash: [u8; 32] = runtime::get_named_arg(DESCRIPTION_HASH);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let expires_at: u64 = runtime::get_named_arg(EXPIRY);
+    spend_deposit(poster, named_purse(BOUNTY_PURSE), amount);
+    let bounty_id = next_id(BOUNTY_COUNT);
+    set_dictionary_value(
+        BOUNTIES,
+        &bounty_id.to_string(),
+        Bounty {
+            poster,
+            description_hash,
+            amount,
+            expires_at,
+            open: true,
+        },
+    );
+    runtime::ret(CLValue::from_t(bounty_id).unwrap_or_revert());
+}
+
+// Submits a claim of the caller on an open bounty, replacing any earlier evidence of theirs.
+// The poster cannot claim their own bounty.
+#[no_mangle]
+pub extern "C" fn submit_claim() {
+    let (bounty_id, bounty) = bounty_arg();
+    if current_time() >= bounty.expires_at {
+        runtime::revert(Error::BountyExpired);
+    }
+    let hunter = runtime::get_caller();
+    if hunter == bounty.poster {
+        runtime::revert(Error::PosterCannotClaim);
+    }
+    let evidence_hash: [u8; 32] = runtime::get_named_arg(EVIDENCE_HASH);
+    set_dictionary_value(
+        BOUNTY_CLAIMS,
+        &dictionary_key(&(bounty_id, hunter)),
+        evidence_hash,
+    );
+}
+
+// Poster only. Awards the reward to `claimant`, who must have submitted a claim.
+// This is still possible after the expiry, as long as the poster has not taken the reward back.
+#[no_mangle]
+pub extern "C" fn award() {
+    let (bounty_id, bounty) = bounty_arg();
+    let claimant: AccountHash = runtime::get_named_arg(CLAIMANT);
+    let claim: Option<[u8; 32]> =
+        get_dictionary_value(BOUNTY_CLAIMS, &dictionary_key(&(bounty_id, claimant)));
+    if claim.is_none() {
+        runtime::revert(Error::ClaimNotFound);
+    }
+    close_bounty(bounty_id, bounty, claimant);
+}
+
+// Poster only. Credits the reward of an expired bounty that nobody was awarded back to the deposit of the poster.
+#[no_mangle]
+pub extern "C" fn refund_bounty() {
+    let (bounty_id, bounty) = bounty_arg();
+    if current_time() < bounty.expires_at {
+        runtime::revert(Error::BountyNotExpired);
+    }
+    let poster = bounty.poster;
+    close_bounty(bounty_id, bounty, poster);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        POST_BOUNTY,
+        vec![
+            Parameter::new(DESCRIPTION_HASH, <[u8; 32]>::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(EXPIRY, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        SUBMIT_CLAIM,
+        vec![
+            Parameter::new(BOUNTY_ID, u64::cl_type()),
+            Parameter::new(EVIDENCE_HASH, <[u8; 32]>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        AWARD,
+        vec![
+            Parameter::new(BOUNTY_ID, u64::cl_type()),
+            Parameter::new(CLAIMANT, AccountHash::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        REFUND_BOUNTY,
+        vec![Parameter::new(BOUNTY_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
@@ -0,0 +1,169 @@
+use alloc::{string::ToString, vec};
+use casper_contract::{
+    contract_api::{runtime, system::transfer_from_purse_to_purse},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, URef, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, BENEFICIARY, CAMPAIGNS, CAMPAIGN_COUNT, CAMPAIGN_ID, CAMPAIGN_PURSE, CONTRIBUTE,
+        CONTRIBUTIONS, CREATE_CAMPAIGN, DEADLINE, DEPOSIT_PURSE, FINALIZE_CAMPAIGN, GOAL,
+        REFUND_CONTRIBUTION,
+    },
+    credit_deposit,
+    error::Error,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, named_purse, next_id,
+        set_dictionary_value,
+    },
+};
+
+// Crowdfunding campaigns collect contributions until their deadline, keeping track of every contributor.
+// Once the goal is met, anyone can finalize the campaign, which credits the funds raised to the deposit of
+// the beneficiary and closes it for contributions. If the deadline passes without the goal being met,
+// every contributor can get their contribution credited back to their own deposit.
+struct Campaign {
+    beneficiary: AccountHash,
+    goal: U512,
+    deadline: u64,
+    raised: U512,
+    finalized: bool,
+}
+
+stored_struct!(Campaign {
+    beneficiary,
+    goal,
+    deadline,
+    raised,
+    finalized,
+});
+
+// Reads the campaign passed in as `campaign_id`.
+fn campaign_arg() -> (u64, Campaign) {
+    let campaign_id: u64 = runtime::get_named_arg(CAMPAIGN_ID);
+    let campaign = get_dictionary_value(CAMPAIGNS, &campaign_id.to_string())
+        .unwrap_or_revert_with(Error::CampaignNotFound);
+    (campaign_id, campaign)
+}
+
+fn write_campaign(campaign_id: u64, campaign: Campaign) {
+    set_dictionary_value(CAMPAIGNS, &campaign_id.to_string(), campaign);
+}
+
+// Creates a campaign raising `goal` for `beneficiary` until the block time `deadline`, and returns its id.
+// The deposit purse of the beneficiary is only created once the campaign is finalized.
+#[no_mangle]
+pub extern "C" fn create_campaign() {
+    let goal: U512 = runtime::get_named_arg(GOAL);
+    let deadline: u64 = runtime::get_named_arg(DEADLINE);
+    let beneficiary: AccountHash = runtime::get_named_arg(BENEFICIARY);
+    let campaign_id = next_id(CAMPAIGN_COUNT);
+    write_campaign(
+        campaign_id,
+        Campaign {
+            beneficiary,
+            goal,
+            deadline,
+            raised: U512::zero(),
+            finalized: false,
+        },
+    );
+    runtime::ret(CLValue::from_t(campaign_id).unwrap_or_revert());
+}
+
+// Contributes `amount` out of the passed in purse, booked under the caller.
+// Called by the `contribute_session`, the same way the `deposit_session` calls `deposit`.
+#[no_mangle]
+pub extern "C" fn contribute() {
+    let (campaign_id, mut campaign) = campaign_arg();
+    let incoming_purse: URef = runtime::get_named_arg(DEPOSIT_PURSE);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    if campaign.finalized || current_time() >= campaign.deadline {
+        runtime::revert(Error::CampaignEnded);
+    }
+    transfer_from_purse_to_purse(incoming_purse, named_purse(CAMPAIGN_PURSE), amount, None)
+        .unwrap_or_revert();
+    let contribution_key = dictionary_key(&(campaign_id, runtime::get_caller()));
+    let contribution: U512 =
+        get_dictionary_value(CONTRIBUTIONS, &contribution_key).unwrap_or_default();
+    set_dictionary_value(CONTRIBUTIONS, &contribution_key, contribution + amount);
+    campaign.raised += amount;
+    write_campaign(campaign_id, campaign);
+}
+
+// Credits the funds raised to the deposit of the beneficiary, once the goal is met.
+#[no_mangle]
+pub extern "C" fn finalize_campaign() {
+    let (campaign_id, mut campaign) = campaign_arg();
+    if campaign.finalized {
+        runtime::revert(Error::CampaignEnded);
+    }
+    if campaign.raised < campaign.goal {
+        runtime::revert(Error::GoalNotMet);
+    }
+    credit_deposit(
+        named_purse(CAMPAIGN_PURSE),
+        campaign.beneficiary,
+        campaign.raised,
+    );
+    campaign.finalized = true;
+    write_campaign(campaign_id, campaign);
+}
+
+// Credits the contribution of the caller back to their deposit, once the campaign failed to meet its goal by the deadline.
+#[no_mangle]
+pub extern "C" fn refund_contribution() {
+    let (campaign_id, campaign) = campaign_arg();
+    if current_time() < campaign.deadline || campaign.raised >= campaign.goal {
+        runtime::revert(Error::CampaignNotFailed);
+    }
+    let contributor = runtime::get_caller();
+    let contribution_key = dictionary_key(&(campaign_id, contributor));
+    let contribution: U512 =
+        get_dictionary_value(CONTRIBUTIONS, &contribution_key).unwrap_or_default();
+    if contribution.is_zero() {
+        runtime::revert(Error::NothingToRefund);
+    }
+    set_dictionary_value(CONTRIBUTIONS, &contribution_key, U512::zero());
+    credit_deposit(named_purse(CAMPAIGN_PURSE), contributor, contribution);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_CAMPAIGN,
+        vec![
+            Parameter::new(GOAL, U512::cl_type()),
+            Parameter::new(DEADLINE, u64::cl_type()),
+            Parameter::new(BENEFICIARY, AccountHash::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CONTRIBUTE,
+        vec![
+            Parameter::new(CAMPAIGN_ID, u64::cl_type()),
+            Parameter::new(DEPOSIT_PURSE, URef::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [FINALIZE_CAMPAIGN, REFUND_CONTRIBUTION] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![Parameter::new(CAMPAIGN_ID, u64::cl_type())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+use alloc::{string::ToString, vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CHAIN_TIP, CHANNELS, CHANNEL_COUNT, CHANNEL_ID, CHANNEL_PURSE, LINKS, LINK_VALUE,
+        OPEN_CHANNEL, PAYEE, PREIMAGE, RECLAIM_CHANNEL, REDEEM_CHANNEL, TIMEOUT,
+    },
+    credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{current_time, get_dictionary_value, named_purse, next_id, set_dictionary_value},
+};
+
+// PayWord channels pay for micropayments off-chain, with nothing but a blake2b hash chain.
+// The payer picks a secret seed, hashes it n times, and opens the channel with the last hash as the tip
+// of the chain, along with the value of one link. Paying k links means handing the payee the hash that is
+// k links deep, i.e. the one the tip is reached from by hashing it k more times, which only the payer can know.
+// The payee redeems the deepest preimage received, as often as they like until the timeout, and is paid for
+// the links not redeemed before. After the timeout, the payer reclaims the remainder and closes the channel.
+struct Channel {
+    payer: AccountHash,
+    payee: AccountHash,
+    // The tip of the chain, or the last redeemed preimage.
+    head: [u8; 32],
+    redeemed_links: u64,
+    link_value: U512,
+    remaining: U512,
+    expires_at: u64,
+    open: bool,
+}
+
+stored_struct!(Channel {
+    payer,
+    payee,
+    head,
+    redeemed_links,
+    link_value,
+    remaining,
+    expires_at,
+    open,
+});
+
+// Reads the channel passed in as `channel_id`, reverting if it has been closed.
+fn channel_arg() -> (u64, Channel) {
+    let channel_id: u64 = runtime::get_named_arg(CHANNEL_ID);
+    let channel: Channel = get_dictionary_value(CHANNELS, &channel_id.to_string())
+        .unwrap_or_revert_with(Error::ChannelNotFound);
+    if !channel.open {
+        runtime::revert(Error::ChannelClosed);
+    }
+    (channel_id, channel)
+}
+
+fn write_channel(channel_id: u64, channel: Channel) {
+    set_dictionary_value(CHANNELS, &channel_id.to_string(), channel);
+}
+
+// Opens a channel to `payee` locking `amount` out of the callers deposit, and returns its id.
+// The channel can be redeemed from for `timeout` milliseconds of block time, and a timeout too long to end
+// before `u64::MAX` never ends. Every link has to be worth something, which bounds how deep a chain can be redeemed.
+#[no_mangle]
+pub extern "C" fn open_channel() {
+    let payer = runtime::get_caller();
+    let payee: AccountHash = runtime::get_named_arg(PAYEE);
+    let chain_tip: [u8; 32] = runtime::get_named_arg(CHAIN_TIP);
+    let link_value: U512 = runtime::get_named_arg(LINK_VALUE);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let timeout: u64 = runtime::get_named_arg(TIMEOUT);
+    if link_value.is_zero() {
+        runtime::revert(Error::InvalidLinkValue);
+    }
+    get_or_create_deposit_purse(payee);
+    spend_deposit(payer, named_purse(CHANNEL_PURSE), amount);
+    let channel_id = next_id(CHANNEL_COUNT);
+    write_channel(
+        channel_id,
+        Channel {
+            payer,
+            payee,
+            head: chain_tip,
+            redeemed_links: 0,
+            link_value,
+            remaining: amount,
+            expires_at: current_time().saturating_add(timeout),
+            open: true,
+        },
+    );
+    runtime::ret(CLValue::from_t(channel_id).unwrap_or_revert());
+}
+
+// Payee only. Redeems `preimage`, which is `links` deep into the chain counted from the tip,
+// crediting the links not redeemed before to the deposit of the payee.
+#[no_mangle]
+pub extern "C" fn redeem_channel() {
+    let (channel_id, mut channel) = channel_arg();
+    if runtime::get_caller() != channel.payee {
+        runtime::revert(Error::NotPayee);
+    }
+    if current_time() >= channel.expires_at {
+        runtime::revert(Error::ChannelExpired);
+    }
+    let preimage: [u8; 32] = runtime::get_named_arg(PREIMAGE);
+    let links: u64 = runtime::get_named_arg(LINKS);
+    if links <= channel.redeemed_links {
+        runtime::revert(Error::InvalidPreimage);
+    }
+    // The channel only pays for as many links as its remaining amount covers, so deeper preimages are
+    // rejected before they are hashed.
+    let new_links = links - channel.redeemed_links;
+    if U512::from(new_links) > channel.remaining / channel.link_value {
+        runtime::revert(Error::InsufficientBalance);
+    }
+    let payment = channel.link_value * U512::from(new_links);
+    let mut hash = preimage;
+    for _ in 0..new_links {
+        hash = runtime::blake2b(hash);
+    }
+    if hash != channel.head {
+        runtime::revert(Error::InvalidPreimage);
+    }
+    credit_deposit(named_purse(CHANNEL_PURSE), channel.payee, payment);
+    channel.head = preimage;
+    channel.redeemed_links = links;
+    channel.remaining -= payment;
+    write_channel(channel_id, channel);
+}
+
+// Payer only. Closes an expired channel, crediting what the payee did not redeem back to the deposit of the payer.
+#[no_mangle]
+pub extern "C" fn reclaim_channel() {
+    let (channel_id, mut channel) = channel_arg();
+    if runtime::get_caller() != channel.payer {
+        runtime::revert(Error::NotPayer);
+    }
+    if current_time() < channel.expires_at {
+        runtime::revert(Error::ChannelNotExpired);
+    }
+    if !channel.remaining.is_zero() {
+        credit_deposit(named_purse(CHANNEL_PURSE), channel.payer, channel.remaining);
+    }
+    channel.remaining = U512::zero();
+    channel.open = false;
+    write_channel(channel_id, channel);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        OPEN_CHANNEL,
+        vec![
+            Parameter::new(PAYEE, AccountHash::cl_type()),
+            Parameter::new(CHAIN_TIP, <[u8; 32]>::cl_type()),
+            Parameter::new(LINK_VALUE, U512::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(TIMEOUT, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        REDEEM_CHANNEL,
+        vec![
+            Parameter::new(CHANNEL_ID, u64::cl_type()),
+            Parameter::new(PREIMAGE, <[u8; 32]>::cl_type()),
+            Parameter::new(LINKS, u64::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        RECLAIM_CHANNEL,
+        vec![Parameter::new(CHANNEL_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
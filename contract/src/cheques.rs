@@ -0,0 +1,99 @@
+use alloc::vec;
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, bytesrepr::ToBytes, CLType, CLTyped, ContractHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Parameter, PublicKey, U512,
+};
+use core::convert::TryFrom;
+use ed25519_dalek::{Signature, Verifier};
+
+use crate::{
+    constants::{
+        AMOUNT, CHEQUE_KEYS, DEPOSIT_CONTRACT_HASH, EXPIRY, NONCE, PAYER, PUBLIC_KEY,
+        REDEEMED_CHEQUES, REDEEM_CHEQUE, REGISTER_CHEQUE_KEY, SIGNATURE,
+    },
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, read_named_value, set_dictionary_value,
+    },
+};
+
+// Cheques are written off-chain: the payer signs the serialized
+// (contract hash, recipient, amount, nonce, expiry) tuple with the ed25519 key they registered.
+// The recipient redeems the cheque, which moves the amount from the deposit of the payer into their own.
+// The contract hash keeps cheques from being redeemed against another installation of the contract,
+// and every nonce of a payer can only be redeemed once.
+
+// Registers the ed25519 public key that verifies the cheques written by the caller, replacing any earlier one.
+#[no_mangle]
+pub extern "C" fn register_cheque_key() {
+    let public_key: PublicKey = runtime::get_named_arg(PUBLIC_KEY);
+    if !matches!(public_key, PublicKey::Ed25519(_)) {
+        runtime::revert(Error::InvalidPublicKey);
+    }
+    set_dictionary_value(
+        CHEQUE_KEYS,
+        &dictionary_key(&runtime::get_caller()),
+        public_key,
+    );
+}
+
+// Redeems a cheque of `payer` to the caller, reverting if it expired, was redeemed already,
+// or is not signed by the key the payer registered. Like the other deadlines of the contract,
+// the cheque expires at the block time `expiry` itself.
+#[no_mangle]
+pub extern "C" fn redeem_cheque() {
+    let recipient = runtime::get_caller();
+    let payer: AccountHash = runtime::get_named_arg(PAYER);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let nonce: u64 = runtime::get_named_arg(NONCE);
+    let expiry: u64 = runtime::get_named_arg(EXPIRY);
+    let signature: [u8; 64] = runtime::get_named_arg(SIGNATURE);
+    if current_time() >= expiry {
+        runtime::revert(Error::ChequeExpired);
+    }
+    let nonce_key = dictionary_key(&(payer, nonce));
+    if get_dictionary_value::<bool>(REDEEMED_CHEQUES, &nonce_key).unwrap_or_default() {
+        runtime::revert(Error::ChequeAlreadyRedeemed);
+    }
+    let public_key = match get_dictionary_value(CHEQUE_KEYS, &dictionary_key(&payer)) {
+        Some(PublicKey::Ed25519(public_key)) => public_key,
+        _ => runtime::revert(Error::NoChequeKey),
+    };
+    let contract_hash: ContractHash = read_named_value(DEPOSIT_CONTRACT_HASH);
+    let message = (contract_hash, recipient, amount, nonce, expiry)
+        .to_bytes()
+        .unwrap_or_revert();
+    let signature =
+        Signature::try_from(&signature[..]).unwrap_or_revert_with(Error::InvalidSignature);
+    if public_key.verify(&message, &signature).is_err() {
+        runtime::revert(Error::InvalidSignature);
+    }
+    set_dictionary_value(REDEEMED_CHEQUES, &nonce_key, true);
+    spend_deposit(payer, get_or_create_deposit_purse(recipient), amount);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        REGISTER_CHEQUE_KEY,
+        vec![Parameter::new(PUBLIC_KEY, PublicKey::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        REDEEM_CHEQUE,
+        vec![
+            Parameter::new(PAYER, AccountHash::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(NONCE, u64::cl_type()),
+            Parameter::new(EXPIRY, u64::cl_type()),
+            Parameter::new(SIGNATURE, <[u8; 64]>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
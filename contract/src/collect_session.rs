@@ -0,0 +1,47 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use casper_contract::contract_api::{runtime, system};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{runtime_args, ContractHash, RuntimeArgs, U512};
+mod constants;
+use constants::{
+    AMOUNT, COLLECT_TO_PURSE, DEPOSIT_CONTRACT_HASH, TAG, TARGET_PURSE, TARGET_PURSE_NAME,
+    TRANSFER_ID,
+};
+
+// Session code that executes in the callers context.
+// Collects the callers deposit into the purse stored under `purse_name` in the named keys of the account,
+// creating and storing the purse first if it does not exist yet, e.g. a "savings" purse next to the `main_purse`.
+// NOTE: creating a new purse costs 2,5 cspr, consider storing and reusing them.
+#[no_mangle]
+pub extern "C" fn call() {
+    let deposit_contract_hash: ContractHash = runtime::get_named_arg(DEPOSIT_CONTRACT_HASH);
+    let purse_name: String = runtime::get_named_arg(TARGET_PURSE_NAME);
+    let amount: Option<U512> = runtime::get_named_arg(AMOUNT);
+    let id: Option<u64> = runtime::get_named_arg(TRANSFER_ID);
+    let tag: Option<u64> = runtime::get_named_arg(TAG);
+    let target_purse = match runtime::get_key(&purse_name) {
+        Some(purse_key) => purse_key.into_uref().unwrap_or_revert(),
+        None => {
+            let new_purse = system::create_purse();
+            runtime::put_key(&purse_name, new_purse.into());
+            new_purse
+        }
+    };
+    // The contract only has to add motes to the purse, so we strip every other access right
+    // from the URef before handing it over.
+    let _: () = runtime::call_contract(
+        deposit_contract_hash,
+        COLLECT_TO_PURSE,
+        runtime_args! {
+            TARGET_PURSE => target_purse.into_add(),
+            AMOUNT => amount,
+            TRANSFER_ID => id,
+            TAG => tag
+        },
+    );
+}
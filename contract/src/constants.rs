@@ -6,3 +6,238 @@ pub const COLLECT: &str = "collect";
 pub const AMOUNT: &str = "amount";
 pub const GET_DEPOSIT_PURSE: &str = "get_deposit_purse";
 pub const DEPOSIT_CONTRACT_HASH: &str = "deposit_contract_hash";
+pub const OWNER: &str = "owner";
+pub const MINIMUM_DEPOSIT: &str = "minimum_deposit";
+pub const REGISTRATION_REQUIRED: &str = "registration_required";
+pub const REGISTER_RECIPIENT: &str = "register_recipient";
+pub const IS_REGISTERED: &str = "is_registered";
+pub const SET_MINIMUM_DEPOSIT: &str = "set_minimum_deposit";
+pub const SET_REGISTRATION_REQUIRED: &str = "set_registration_required";
+pub const COLLECT_TO_PURSE: &str = "collect_to_purse";
+pub const TARGET_PURSE: &str = "purse";
+pub const TARGET_PURSE_NAME: &str = "purse_name";
+pub const TARGET: &str = "target";
+pub const SET_WITHDRAWAL_ADDRESS: &str = "set_withdrawal_address";
+pub const WITHDRAWAL_ADDRESS: &str = "withdrawal_address";
+pub const WITHDRAWAL_ADDRESSES: &str = "withdrawal_addresses";
+pub const PENDING_WITHDRAWAL_ADDRESSES: &str = "pending_withdrawal_addresses";
+// Changing a configured withdrawal address takes a day (in milliseconds of block time).
+pub const WITHDRAWAL_ADDRESS_DELAY: u64 = 24 * 60 * 60 * 1000;
+pub const TRANSFER_ID: &str = "id";
+pub const TAG: &str = "tag";
+pub const TAG_BALANCES: &str = "tag_balances";
+pub const TAGGED_TOTALS: &str = "tagged_totals";
+pub const TAG_GENERATIONS: &str = "tag_generations";
+pub const GET_DEPOSIT_BALANCE: &str = "get_deposit_balance";
+pub const OPEN_ESCROW: &str = "open_escrow";
+pub const RELEASE_ESCROW: &str = "release_escrow";
+pub const REFUND_ESCROW: &str = "refund_escrow";
+pub const DISPUTE_ESCROW: &str = "dispute_escrow";
+pub const SETTLE_ESCROW: &str = "settle_escrow";
+pub const ESCROW_ID: &str = "escrow_id";
+pub const PAYEE: &str = "payee";
+pub const ARBITER: &str = "arbiter";
+pub const PAYEE_AMOUNT: &str = "payee_amount";
+pub const ESCROWS: &str = "escrows";
+pub const ESCROW_COUNT: &str = "escrow_count";
+pub const ESCROW_PURSE: &str = "escrow_purse";
+pub const OPEN_MILESTONE_ESCROW: &str = "open_milestone_escrow";
+pub const APPROVE_MILESTONE: &str = "approve_milestone";
+pub const CANCEL_MILESTONE: &str = "cancel_milestone";
+pub const MILESTONES: &str = "milestones";
+pub const MILESTONE: &str = "milestone";
+pub const MILESTONE_ESCROWS: &str = "milestone_escrows";
+pub const MILESTONE_ESCROW_COUNT: &str = "milestone_escrow_count";
+pub const OPEN_OPTIMISTIC_ESCROW: &str = "open_optimistic_escrow";
+pub const CLAIM_ESCROW: &str = "claim_escrow";
+pub const CHALLENGE_WINDOW: &str = "challenge_window";
+pub const SUBSCRIBE: &str = "subscribe";
+pub const CHARGE: &str = "charge";
+pub const CANCEL_SUBSCRIPTION: &str = "cancel_subscription";
+pub const GET_MERCHANT_SUBSCRIPTIONS: &str = "get_merchant_subscriptions";
+pub const SUBSCRIPTION_ID: &str = "subscription_id";
+pub const MERCHANT: &str = "merchant";
+pub const PERIOD: &str = "period";
+pub const FUNDING: &str = "funding";
+pub const SUBSCRIPTIONS: &str = "subscriptions";
+pub const MERCHANT_SUBSCRIPTIONS: &str = "merchant_subscriptions";
+pub const SUBSCRIPTION_COUNT: &str = "subscription_count";
+pub const SUBSCRIPTION_PURSE: &str = "subscription_purse";
+pub const SCHEDULE_PAYMENT: &str = "schedule_payment";
+pub const EXECUTE_DUE: &str = "execute_due";
+pub const CANCEL_PAYMENT: &str = "cancel_payment";
+pub const SET_KEEPER_TIP: &str = "set_keeper_tip";
+pub const EXECUTE_AFTER: &str = "execute_after";
+pub const PAYMENT_ID: &str = "payment_id";
+pub const PAYMENT_IDS: &str = "payment_ids";
+pub const KEEPER_TIP: &str = "keeper_tip";
+pub const SCHEDULED_PAYMENTS: &str = "scheduled_payments";
+pub const SCHEDULED_PAYMENT_COUNT: &str = "scheduled_payment_count";
+pub const SCHEDULED_PAYMENT_PURSE: &str = "scheduled_payment_purse";
+pub const FUND_DISTRIBUTION: &str = "fund_distribution";
+pub const CLAIM: &str = "claim";
+pub const DISTRIBUTION_ID: &str = "distribution_id";
+pub const MERKLE_ROOT: &str = "merkle_root";
+pub const PROOF: &str = "proof";
+pub const DISTRIBUTIONS: &str = "distributions";
+pub const DISTRIBUTION_CLAIMS: &str = "distribution_claims";
+pub const DISTRIBUTION_COUNT: &str = "distribution_count";
+pub const DISTRIBUTION_PURSE: &str = "distribution_purse";
+pub const OPEN_CHANNEL: &str = "open_channel";
+pub const REDEEM_CHANNEL: &str = "redeem_channel";
+pub const RECLAIM_CHANNEL: &str = "reclaim_channel";
+pub const CHANNEL_ID: &str = "channel_id";
+pub const CHAIN_TIP: &str = "chain_tip";
+pub const LINK_VALUE: &str = "link_value";
+pub const TIMEOUT: &str = "timeout";
+pub const PREIMAGE: &str = "preimage";
+pub const LINKS: &str = "links";
+pub const CHANNELS: &str = "channels";
+pub const CHANNEL_COUNT: &str = "channel_count";
+pub const CHANNEL_PURSE: &str = "channel_purse";
+pub const INIT: &str = "init";
+pub const REGISTER_CHEQUE_KEY: &str = "register_cheque_key";
+pub const REDEEM_CHEQUE: &str = "redeem_cheque";
+pub const PUBLIC_KEY: &str = "public_key";
+pub const PAYER: &str = "payer";
+pub const NONCE: &str = "nonce";
+pub const EXPIRY: &str = "expiry";
+pub const SIGNATURE: &str = "signature";
+pub const CHEQUE_KEYS: &str = "cheque_keys";
+pub const REDEEMED_CHEQUES: &str = "redeemed_cheques";
+pub const CREATE_CAMPAIGN: &str = "create_campaign";
+pub const CONTRIBUTE: &str = "contribute";
+pub const FINALIZE_CAMPAIGN: &str = "finalize_campaign";
+pub const REFUND_CONTRIBUTION: &str = "refund_contribution";
+pub const CAMPAIGN_ID: &str = "campaign_id";
+pub const GOAL: &str = "goal";
+pub const DEADLINE: &str = "deadline";
+pub const BENEFICIARY: &str = "beneficiary";
+pub const CAMPAIGNS: &str = "campaigns";
+pub const CONTRIBUTIONS: &str = "contributions";
+pub const CAMPAIGN_COUNT: &str = "campaign_count";
+pub const CAMPAIGN_PURSE: &str = "campaign_purse";
+pub const CREATE_BILL: &str = "create_bill";
+pub const PAY_BILL_SHARE: &str = "pay_bill_share";
+pub const COLLECT_BILL: &str = "collect_bill";
+pub const CANCEL_BILL: &str = "cancel_bill";
+pub const WITHDRAW_BILL_SHARE: &str = "withdraw_bill_share";
+pub const BILL_ID: &str = "bill_id";
+pub const SHARES: &str = "shares";
+pub const EXPIRES_AT: &str = "expires_at";
+pub const BILLS: &str = "bills";
+pub const BILL_COUNT: &str = "bill_count";
+pub const BILL_PURSE: &str = "bill_purse";
+pub const CREATE_CIRCLE: &str = "create_circle";
+pub const PAY_ROUND: &str = "pay_round";
+pub const PAYOUT_ROUND: &str = "payout_round";
+pub const EXIT_CIRCLE: &str = "exit_circle";
+pub const CIRCLE_ID: &str = "circle_id";
+pub const MEMBERS: &str = "members";
+pub const CONTRIBUTION: &str = "contribution";
+pub const PENALTY: &str = "penalty";
+pub const ROUND_LENGTH: &str = "round_length";
+pub const CIRCLES: &str = "circles";
+pub const CIRCLE_COUNT: &str = "circle_count";
+pub const CIRCLE_PURSE: &str = "circle_purse";
+pub const POST_BOUNTY: &str = "post_bounty";
+pub const SUBMIT_CLAIM: &str = "submit_claim";
+pub const AWARD: &str = "award";
+pub const REFUND_BOUNTY: &str = "refund_bounty";
+pub const BOUNTY_ID: &str = "bounty_id";
+pub const DESCRIPTION_HASH: &str = "description_hash";
+pub const EVIDENCE_HASH: &str = "evidence_hash";
+pub const CLAIMANT: &str = "claimant";
+pub const BOUNTIES: &str = "bounties";
+pub const BOUNTY_CLAIMS: &str = "bounty_claims";
+pub const BOUNTY_COUNT: &str = "bounty_count";
+pub const BOUNTY_PURSE: &str = "bounty_purse";
+pub const FUND_MATCHING_POOL: &str = "fund_matching_pool";
+pub const CLOSE_MATCHING_POOL: &str = "close_matching_pool";
+pub const GET_MATCHING_POOL_REMAINING: &str = "get_matching_pool_remaining";
+pub const GET_TOTAL_MATCHED: &str = "get_total_matched";
+pub const MATCHING_POOL_ID: &str = "matching_pool_id";
+pub const CHARITY: &str = "charity";
+pub const RATIO: &str = "ratio";
+pub const MATCHING_POOLS: &str = "matching_pools";
+pub const CHARITY_MATCHING_POOLS: &str = "charity_matching_pools";
+pub const MATCHING_POOL_COUNT: &str = "matching_pool_count";
+pub const MATCHING_PURSE: &str = "matching_purse";
+pub const SET_BENEFICIARY: &str = "set_beneficiary";
+pub const HEARTBEAT: &str = "heartbeat";
+pub const CLAIM_INACTIVE_DEPOSIT: &str = "claim_inactive_deposit";
+pub const INACTIVITY_PERIOD: &str = "inactivity_period";
+pub const DEAD_MAN_SWITCHES: &str = "dead_man_switches";
+pub const SET_GUARDIANS: &str = "set_guardians";
+pub const APPROVE_RECOVERY: &str = "approve_recovery";
+pub const VETO_RECOVERY: &str = "veto_recovery";
+pub const EXECUTE_RECOVERY: &str = "execute_recovery";
+pub const GUARDIANS: &str = "guardians";
+pub const THRESHOLD: &str = "threshold";
+pub const RECOVERY_DELAY: &str = "recovery_delay";
+pub const NEW_ACCOUNT: &str = "new_account";
+pub const GUARDIAN_CONFIGS: &str = "guardian_configs";
+pub const RECOVERY_APPROVALS: &str = "recovery_approvals";
+pub const SET_VAULT_DELAY: &str = "set_vault_delay";
+pub const REQUEST_WITHDRAWAL: &str = "request_withdrawal";
+pub const FINALIZE_WITHDRAWAL: &str = "finalize_withdrawal";
+pub const CANCEL_WITHDRAWAL: &str = "cancel_withdrawal";
+pub const WITHDRAWAL_DELAY: &str = "withdrawal_delay";
+pub const VAULTS: &str = "vaults";
+pub const SET_DAILY_LIMIT: &str = "set_daily_limit";
+pub const GET_REMAINING_ALLOWANCE: &str = "get_remaining_allowance";
+pub const DAILY_LIMIT: &str = "daily_limit";
+pub const DAILY_LIMITS: &str = "daily_limits";
+// Daily limits count the collects of the last 24 hours (in milliseconds of block time).
+pub const DAILY_LIMIT_WINDOW: u64 = 24 * 60 * 60 * 1000;
+// Raising a daily limit takes a day (in milliseconds of block time).
+pub const DAILY_LIMIT_DELAY: u64 = 24 * 60 * 60 * 1000;
+pub const CREATE_JOINT_ACCOUNT: &str = "create_joint_account";
+pub const PROPOSE_JOINT_COLLECT: &str = "propose_joint_collect";
+pub const PROPOSE_ADD_OWNER: &str = "propose_add_owner";
+pub const PROPOSE_REMOVE_OWNER: &str = "propose_remove_owner";
+pub const APPROVE_JOINT_PROPOSAL: &str = "approve_joint_proposal";
+pub const JOINT_ACCOUNT: &str = "joint_account";
+pub const OWNERS: &str = "owners";
+pub const PROPOSAL_ID: &str = "proposal_id";
+pub const JOINT_ACCOUNTS: &str = "joint_accounts";
+pub const JOINT_ACCOUNT_COUNT: &str = "joint_account_count";
+pub const JOINT_PROPOSALS: &str = "joint_proposals";
+pub const JOINT_PROPOSAL_COUNT: &str = "joint_proposal_count";
+pub const CREATE_TREASURY: &str = "create_treasury";
+pub const CREATE_SPENDING_PROPOSAL: &str = "create_spending_proposal";
+pub const VOTE: &str = "vote";
+pub const EXECUTE_SPENDING_PROPOSAL: &str = "execute_spending_proposal";
+pub const TREASURY: &str = "treasury";
+pub const QUORUM: &str = "quorum";
+pub const VOTING_PERIOD: &str = "voting_period";
+pub const MEMO: &str = "memo";
+pub const SUPPORT: &str = "support";
+pub const TREASURIES: &str = "treasuries";
+pub const TREASURY_COUNT: &str = "treasury_count";
+pub const SPENDING_PROPOSALS: &str = "spending_proposals";
+pub const SPENDING_PROPOSAL_COUNT: &str = "spending_proposal_count";
+pub const CREATE_GOAL: &str = "create_goal";
+pub const SAVE_TO_GOAL: &str = "save_to_goal";
+pub const WITHDRAW_GOAL: &str = "withdraw_goal";
+pub const GET_GOAL_BALANCE: &str = "get_goal_balance";
+pub const GOAL_NAME: &str = "goal_name";
+pub const TARGET_AMOUNT: &str = "target_amount";
+pub const UNLOCK_AT: &str = "unlock_at";
+pub const PENALTY_RATE: &str = "penalty_rate";
+pub const PENALTY_RECIPIENT: &str = "penalty_recipient";
+pub const SAVINGS_GOALS: &str = "savings_goals";
+pub const SAVINGS_GOAL_PURSE: &str = "savings_goal_purse";
+pub const ENTRY_POINT: &str = "entry_point";
+pub const QUERY_RESULT: &str = "query_result";
+pub const RECLAIM_DISTRIBUTION: &str = "reclaim_distribution";
+pub const MARK_DEFAULTED: &str = "mark_defaulted";
+pub const MEMBER: &str = "member";
+// Matching pools match at most ten times the donation, in basis points.
+pub const MAX_MATCHING_RATIO: u32 = 100_000;
+// A charity can have this many matching pools at once, as every deposit to it runs through all of them.
+pub const MAX_MATCHING_POOLS: usize = 10;
+// A matching pool is funded with at least 100 CSPR, so the pools of a charity cannot be filled up with dust.
+pub const MIN_MATCHING_POOL: u64 = 100_000_000_000;
+// A dead man's switch waits for at least a day of inactivity (in milliseconds of block time).
+pub const MIN_INACTIVITY_PERIOD: u64 = 24 * 60 * 60 * 1000;
@@ -0,0 +1,40 @@
+#![no_main]
+#![no_std]
+
+use casper_contract::contract_api::{account, runtime, system};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{runtime_args, ContractHash, RuntimeArgs, U512};
+mod constants;
+use constants::{AMOUNT, CAMPAIGN_ID, CONTRIBUTE, DEPOSIT_CONTRACT_HASH, DEPOSIT_PURSE};
+
+// Same transport purse as the one of the deposit session.
+pub const MY_TRANSFER_PURSE: &str = "my_transfer_purse";
+
+// Session code that contributes `amount` from the main purse of the caller to a crowdfunding campaign.
+// Like the deposit session, it moves the motes through a transport purse stored in the account,
+// and passes that purse to the `contribute` entry point, which books the contribution under the caller.
+#[no_mangle]
+pub extern "C" fn call() {
+    let deposit_contract_hash: ContractHash = runtime::get_named_arg(DEPOSIT_CONTRACT_HASH);
+    let campaign_id: u64 = runtime::get_named_arg(CAMPAIGN_ID);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let transport_purse = match runtime::get_key(MY_TRANSFER_PURSE) {
+        Some(purse_key) => purse_key.into_uref().unwrap_or_revert(),
+        None => {
+            let new_purse = system::create_purse();
+            runtime::put_key(MY_TRANSFER_PURSE, new_purse.into());
+            new_purse
+        }
+    };
+    system::transfer_from_purse_to_purse(account::get_main_purse(), transport_purse, amount, None)
+        .unwrap_or_revert();
+    let _: () = runtime::call_contract(
+        deposit_contract_hash,
+        CONTRIBUTE,
+        runtime_args! {
+            CAMPAIGN_ID => campaign_id,
+            DEPOSIT_PURSE => transport_purse,
+            AMOUNT => amount
+        },
+    );
+}
@@ -0,0 +1,192 @@
+use alloc::vec;
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter,
+};
+
+use crate::{
+    change_delay,
+    constants::{
+        BENEFICIARY, CLAIM_INACTIVE_DEPOSIT, DEAD_MAN_SWITCHES, DEPOSIT_RECIPIENT, HEARTBEAT,
+        INACTIVITY_PERIOD, MIN_INACTIVITY_PERIOD, SET_BENEFICIARY,
+    },
+    deposit_purse,
+    error::Error,
+    limits, tags,
+    utils::{current_time, dictionary_key, get_dictionary_value, set_dictionary_value},
+    vault,
+    withdrawal_address::withdrawal_address,
+};
+
+// A dead man's switch names a beneficiary who can collect the whole deposit of the recipient once the
+// recipient has not been active for `inactivity_period` milliseconds of block time, e.g. because their
+// key became unreachable. Every `collect`, `collect_to_purse` and `heartbeat` of the recipient counts as activity,
+// and so does every payment flow they fund out of their deposit. The period is at least `MIN_INACTIVITY_PERIOD`,
+// so a beneficiary cannot claim a deposit right after a stolen key named them.
+// While the deposit is in vault mode or has a daily limit, naming a beneficiary only takes effect after the
+// `change_delay`. A deposit in vault mode cannot be claimed at all, and claims count against the daily limit,
+// so a switch cannot be used to get around either.
+struct DeadManSwitch {
+    beneficiary: Option<AccountHash>,
+    inactivity_period: u64,
+    last_active_at: u64,
+    // A beneficiary and inactivity period waiting for the change delay, and the block time they take effect at.
+    pending: Option<(AccountHash, u64, u64)>,
+}
+
+stored_struct!(DeadManSwitch {
+    beneficiary,
+    inactivity_period,
+    last_active_at,
+    pending,
+});
+
+impl DeadManSwitch {
+    // Applies a pending change once its delay has passed. The timer of the new beneficiary starts no earlier
+    // than the change takes effect.
+    fn settle(&mut self) {
+        if let Some((beneficiary, inactivity_period, effective_at)) = self.pending {
+            if effective_at <= current_time() {
+                self.beneficiary = Some(beneficiary);
+                self.inactivity_period = inactivity_period;
+                self.last_active_at = self.last_active_at.max(effective_at);
+                self.pending = None;
+            }
+        }
+    }
+}
+
+fn dead_man_switch(recipient: AccountHash) -> Option<DeadManSwitch> {
+    get_dictionary_value::<Option<DeadManSwitch>>(DEAD_MAN_SWITCHES, &dictionary_key(&recipient))
+        .flatten()
+        .map(|mut switch| {
+            switch.settle();
+            switch
+        })
+}
+
+fn set_dead_man_switch(recipient: AccountHash, switch: Option<DeadManSwitch>) {
+    set_dictionary_value(DEAD_MAN_SWITCHES, &dictionary_key(&recipient), switch);
+}
+
+// Resets the inactivity timer of the recipient, if they named a beneficiary.
+pub fn record_activity(recipient: AccountHash) {
+    if let Some(mut switch) = dead_man_switch(recipient) {
+        switch.last_active_at = current_time();
+        set_dead_man_switch(recipient, Some(switch));
+    }
+}
+
+// Names the beneficiary of the callers deposit along with the inactivity period, and starts the timer.
+// While the deposit is in vault mode or has a daily limit, the new beneficiary only takes effect after the
+// change delay, and the earlier one stays in place until then. Passing `None` as the beneficiary removes
+// the switch right away.
+#[no_mangle]
+pub extern "C" fn set_beneficiary() {
+    let recipient = runtime::get_caller();
+    let beneficiary: Option<AccountHash> = runtime::get_named_arg(BENEFICIARY);
+    let inactivity_period: u64 = runtime::get_named_arg(INACTIVITY_PERIOD);
+    let beneficiary = match beneficiary {
+        Some(beneficiary) => beneficiary,
+        None => {
+            set_dead_man_switch(recipient, None);
+            return;
+        }
+    };
+    if inactivity_period < MIN_INACTIVITY_PERIOD {
+        runtime::revert(Error::InvalidInactivityPeriod);
+    }
+    let now = current_time();
+    let mut switch = dead_man_switch(recipient).unwrap_or(DeadManSwitch {
+        beneficiary: None,
+        inactivity_period,
+        last_active_at: now,
+        pending: None,
+    });
+    let delay = change_delay(recipient);
+    if delay == 0 {
+        switch.beneficiary = Some(beneficiary);
+        switch.inactivity_period = inactivity_period;
+        switch.last_active_at = now;
+        switch.pending = None;
+    } else {
+        switch.pending = Some((beneficiary, inactivity_period, now.saturating_add(delay)));
+    }
+    set_dead_man_switch(recipient, Some(switch));
+}
+
+// Resets the inactivity timer of the caller without collecting anything.
+#[no_mangle]
+pub extern "C" fn heartbeat() {
+    let recipient = runtime::get_caller();
+    if dead_man_switch(recipient).is_none() {
+        runtime::revert(Error::NoBeneficiary);
+    }
+    record_activity(recipient);
+}
+
+// Beneficiary only. Transfers the whole deposit of `recipient` to the beneficiary, once the recipient
+// has been inactive for the whole period, which never happens for a period that would end after `u64::MAX`.
+// A withdrawal address pins where a deposit can go, so a deposit with one configured cannot be claimed
+// this way, which also keeps a stolen key from using a switch to get around the address. For the same
+// reason, a deposit in vault mode cannot be claimed. With a daily limit, only what is left of the
+// allowance is transferred, and the beneficiary claims the rest on the following days.
+#[no_mangle]
+pub extern "C" fn claim_inactive_deposit() {
+    let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let beneficiary = runtime::get_caller();
+    let switch = match dead_man_switch(recipient) {
+        Some(switch) if switch.beneficiary == Some(beneficiary) => switch,
+        _ => runtime::revert(Error::NotBeneficiary),
+    };
+    let inactive_at = switch
+        .last_active_at
+        .saturating_add(switch.inactivity_period);
+    if current_time() < inactive_at {
+        runtime::revert(Error::RecipientStillActive);
+    }
+    if withdrawal_address(recipient).is_some() {
+        runtime::revert(Error::WithdrawalAddressLocked);
+    }
+    vault::require_unlocked(recipient);
+    tags::clear(recipient);
+    let deposit_purse = deposit_purse(recipient);
+    let amount =
+        limits::record_withdrawal_up_to(recipient, tags::balance(recipient, deposit_purse, None));
+    tags::debit(recipient, deposit_purse, amount, None);
+    system::transfer_from_purse_to_account(deposit_purse, beneficiary, amount, None)
+        .unwrap_or_revert();
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_BENEFICIARY,
+        vec![
+            Parameter::new(BENEFICIARY, Option::<AccountHash>::cl_type()),
+            Parameter::new(INACTIVITY_PERIOD, u64::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        HEARTBEAT,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CLAIM_INACTIVE_DEPOSIT,
+        vec![Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
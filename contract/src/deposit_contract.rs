@@ -14,78 +14,281 @@ use casper_contract::{
     unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_types::{
-    ApiError, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints,
-    Key, Parameter, URef, U512,
+    account::AccountHash, contracts::NamedKeys, runtime_args, CLType, CLTyped, CLValue,
+    ContractHash, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter,
+    RuntimeArgs, URef, U512,
 };
 
+#[macro_use]
+mod macros;
+
+mod airdrop;
+mod bills;
+mod bounties;
+mod campaigns;
+mod channels;
+mod cheques;
 mod constants;
-use constants::{AMOUNT, COLLECT, DEPOSIT, DEPOSIT_PURSE, DEPOSIT_RECIPIENT, GET_DEPOSIT_PURSE};
+mod dead_man_switch;
+mod error;
+mod escrow;
+mod goals;
+mod guardians;
+mod joint;
+mod limits;
+mod matching;
+mod milestones;
+mod rosca;
+mod scheduled_payments;
+mod subscriptions;
+mod tags;
+mod treasury;
+mod utils;
+mod vault;
+mod withdrawal_address;
+use constants::{
+    AMOUNT, COLLECT, COLLECT_TO_PURSE, DAILY_LIMIT_DELAY, DEPOSIT, DEPOSIT_CONTRACT_HASH,
+    DEPOSIT_PURSE, DEPOSIT_RECIPIENT, GET_DEPOSIT_BALANCE, GET_DEPOSIT_PURSE, INIT, IS_REGISTERED,
+    KEEPER_TIP, MINIMUM_DEPOSIT, OWNER, REGISTER_RECIPIENT, REGISTRATION_REQUIRED,
+    SET_MINIMUM_DEPOSIT, SET_REGISTRATION_REQUIRED, TAG, TARGET, TARGET_PURSE, TRANSFER_ID,
+};
+use error::Error;
+use utils::{read_named_value, require_owner, write_named_value};
+use withdrawal_address::withdrawal_address;
+
+// Creates a new deposit purse and stores it in the contract under the recipient's account hash.
+fn create_deposit_purse(recipient: AccountHash) -> URef {
+    let new_purse = create_purse();
+    runtime::put_key(&recipient.to_string(), new_purse.into());
+    new_purse
+}
+
+// Returns the deposit purse stored for the recipient, creating it if there is none yet.
+// While registration is required, only `register_recipient` may create new purses,
+// so depositing to a recipient that has not opted in reverts.
+fn get_or_create_deposit_purse(recipient: AccountHash) -> URef {
+    match runtime::get_key(&recipient.to_string()) {
+        Some(key) => key.into_uref().unwrap_or_revert(),
+        None => {
+            if read_named_value::<bool>(REGISTRATION_REQUIRED) {
+                runtime::revert(Error::RecipientNotRegistered);
+            }
+            create_deposit_purse(recipient)
+        }
+    }
+}
 
 // When depositing, the contract checks if the recipient had a deposit purse stored previously
 // and if not, creates a new purse inside the contract for them (purse creation costs 2,5 cspr).
 // Then the amount is transfered into the purse that is stored in the contract.
 // Creating a purse in this process is a one time per user cost, since the purses are stored.
+// The optional `id` is attached to the transfer, so integrations can match it against their records.
+// The optional `tag` books the deposit under a sub-ledger of the recipient, see `tags.rs`.
+// Deposits to a charity with matching pools are matched on top, see `matching.rs`.
+// Deposits below the configured minimum revert with `Error::BelowMinimumDeposit`, so the contract
+// cannot be flooded with purses holding dust.
 #[no_mangle]
 pub extern "C" fn deposit() {
     let incoming_purse: URef = runtime::get_named_arg(DEPOSIT_PURSE);
     let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
     let amount: Option<U512> = runtime::get_named_arg(AMOUNT);
-    let recipient_account_str = recipient.into_account().unwrap_or_revert().to_string();
-    let deposit_purse = match runtime::get_key(&recipient_account_str) {
-        Some(key) => key.into_uref().unwrap_or_revert(),
-        None => {
-            let new_purse = create_purse();
-            runtime::put_key(&recipient_account_str, new_purse.into());
-            new_purse
-        }
-    };
+    let id: Option<u64> = runtime::get_named_arg(TRANSFER_ID);
+    let tag: Option<u64> = runtime::get_named_arg(TAG);
+    let recipient = recipient.into_account().unwrap_or_revert();
+    let deposit_purse = get_or_create_deposit_purse(recipient);
     let transfer_amount = match amount {
         Some(amount) => amount,
         None => get_purse_balance(deposit_purse).unwrap_or_revert(),
     };
-    transfer_from_purse_to_purse(incoming_purse, deposit_purse, transfer_amount, None)
+    if transfer_amount < read_named_value::<U512>(MINIMUM_DEPOSIT) {
+        runtime::revert(Error::BelowMinimumDeposit);
+    }
+    transfer_from_purse_to_purse(incoming_purse, deposit_purse, transfer_amount, id)
         .unwrap_or_revert();
+    if let Some(tag) = tag {
+        tags::credit(recipient, tag, transfer_amount);
+    }
+    matching::match_deposit(recipient, transfer_amount);
 }
 
 // A possible design choice to decrease the amount of purses created is to return a URef to a purse for
 // the caller to deposit into. In this case you would want to limit the access rights for the purse
 // to ADD, or depending on the use case ADD and READ, so the caller can only read the balance,
 // and make the deposit into the purse.
+// NOTE: the contract cannot enforce the minimum deposit here, as the transfer happens after this call.
+// Enable `registration_required` to stop this entry point from creating purses for arbitrary recipients.
 #[no_mangle]
 pub extern "C" fn get_deposit_purse() {
     let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
-    let recipient_account_str = recipient.into_account().unwrap_or_revert().to_string();
-    let deposit_purse = match runtime::get_key(&recipient_account_str) {
-        Some(key) => key.into_uref().unwrap_or_revert(),
-        None => {
-            let new_purse = create_purse();
-            runtime::put_key(&recipient_account_str, new_purse.into());
-            new_purse
-        }
-    };
+    let deposit_purse = get_or_create_deposit_purse(recipient.into_account().unwrap_or_revert());
     runtime::ret(CLValue::from_t(deposit_purse.into_add()).unwrap_or_revert());
 }
 
-// The `collect` entry_point checks whether there have been a deposit for the caller. If not then the call reverts with User(1) error.
-// If a deposit purse is found, the desired amount (if contained within the purse) will be transfered directly to the callers account.
-#[no_mangle]
-pub extern "C" fn collect() {
-    let recipient = get_caller();
-    let deposit_purse = match runtime::get_key(&recipient.to_string()) {
+// Looks up the deposit purse of the recipient, reverting with User(1) error if there is none.
+fn deposit_purse(recipient: AccountHash) -> URef {
+    match runtime::get_key(&recipient.to_string()) {
         Some(key) => key.into_uref().unwrap_or_revert(),
-        None => runtime::revert(ApiError::User(1)),
+        None => runtime::revert(Error::NoDepositPurse),
+    }
+}
+
+// Moves `amount` out of the deposit of `payer` into `purse`, to fund one of the payment flows of the contract.
+// A withdrawal address pins where a deposit can go, so a deposit with one configured cannot be spent this way,
+// and neither can a deposit in vault mode. Spending counts against the daily limit of the payer, and as
+// activity for their dead man's switch.
+fn spend_deposit(payer: AccountHash, purse: URef, amount: U512) {
+    if withdrawal_address(payer).is_some() {
+        runtime::revert(Error::WithdrawalAddressLocked);
+    }
+    vault::require_unlocked(payer);
+    let deposit_purse = deposit_purse(payer);
+    tags::debit(payer, deposit_purse, amount, None);
+    limits::record_withdrawal(payer, amount);
+    dead_man_switch::record_activity(payer);
+    transfer_from_purse_to_purse(deposit_purse, purse, amount, None).unwrap_or_revert();
+}
+
+// How long a change of who else can take the deposit of the recipient, their beneficiary or their guardians,
+// waits before it takes effect. In vault mode that is the withdrawal delay, and with a daily limit at least
+// `DAILY_LIMIT_DELAY`, so a stolen key cannot name itself to get around either.
+fn change_delay(recipient: AccountHash) -> u64 {
+    let limit_delay = if limits::has_limit(recipient) {
+        DAILY_LIMIT_DELAY
+    } else {
+        0
     };
+    vault::current_delay(recipient).max(limit_delay)
+}
+
+// Moves `amount` held by one of the payment flows from `purse` into the deposit of the recipient,
+// from where the recipient can collect it.
+fn credit_deposit(purse: URef, recipient: AccountHash, amount: U512) {
+    let deposit_purse = get_or_create_deposit_purse(recipient);
+    transfer_from_purse_to_purse(purse, deposit_purse, amount, None).unwrap_or_revert();
+}
+
+// Resolves the `amount` and `tag` arguments of the collect entry_points and books the withdrawal.
+// A `None` amount means everything under the tag, or the whole untagged part of the deposit if no tag is given.
+// Collecting also counts as activity of the recipient for their dead man's switch.
+// Deposits in vault mode cannot be collected, see `vault.rs`,
+// and every collect counts against the daily limit of the recipient, see `limits.rs`.
+fn collectable(recipient: AccountHash) -> (URef, U512) {
+    vault::require_unlocked(recipient);
+    let deposit_purse = deposit_purse(recipient);
     let amount: Option<U512> = runtime::get_named_arg(AMOUNT);
+    let tag: Option<u64> = runtime::get_named_arg(TAG);
     let transfer_amount = match amount {
         Some(amount) => amount,
-        None => get_purse_balance(deposit_purse).unwrap_or_revert(),
+        None => tags::available(recipient, deposit_purse, tag),
     };
-    system::transfer_from_purse_to_account(deposit_purse, recipient, transfer_amount, None)
+    tags::debit(recipient, deposit_purse, transfer_amount, tag);
+    limits::record_withdrawal(recipient, transfer_amount);
+    dead_man_switch::record_activity(recipient);
+    (deposit_purse, transfer_amount)
+}
+
+// The `collect` entry_point checks whether there have been a deposit for the caller. If not then the call reverts with User(1) error.
+// If a deposit purse is found, the desired amount (if contained within the purse) will be transfered directly to the callers account,
+// or to the `target` account if one is passed in, e.g. a cold wallet that never signs deploys itself.
+// Once the caller configured a withdrawal address, the deposit can only be collected to that address.
+#[no_mangle]
+pub extern "C" fn collect() {
+    let recipient = get_caller();
+    let target: Option<AccountHash> = runtime::get_named_arg(TARGET);
+    let id: Option<u64> = runtime::get_named_arg(TRANSFER_ID);
+    let (deposit_purse, transfer_amount) = collectable(recipient);
+    let destination = match (withdrawal_address(recipient), target) {
+        (Some(address), Some(target)) if address != target => {
+            runtime::revert(Error::WithdrawalAddressLocked)
+        }
+        (Some(address), _) => address,
+        (None, target) => target.unwrap_or(recipient),
+    };
+    system::transfer_from_purse_to_account(deposit_purse, destination, transfer_amount, id)
+        .unwrap_or_revert();
+}
+
+// Same as `collect`, but the motes go into the purse passed in by the caller instead of their main purse.
+// This lets accounts and contracts route withdrawals into dedicated purses, like a savings purse.
+// The contract only needs ADD access to the target purse, so callers should pass in a URef stripped to `into_add()`.
+#[no_mangle]
+pub extern "C" fn collect_to_purse() {
+    let recipient = get_caller();
+    // A purse can be anyone's, so it is no valid destination once a withdrawal address is configured.
+    if withdrawal_address(recipient).is_some() {
+        runtime::revert(Error::WithdrawalAddressLocked);
+    }
+    let target_purse: URef = runtime::get_named_arg(TARGET_PURSE);
+    let id: Option<u64> = runtime::get_named_arg(TRANSFER_ID);
+    let (deposit_purse, transfer_amount) = collectable(recipient);
+    transfer_from_purse_to_purse(deposit_purse, target_purse, transfer_amount, id)
         .unwrap_or_revert();
 }
 
+// Returns the balance of the recipient under `tag`, or the balance of the whole deposit if no tag is given.
+#[no_mangle]
+pub extern "C" fn get_deposit_balance() {
+    let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let tag: Option<u64> = runtime::get_named_arg(TAG);
+    let recipient = recipient.into_account().unwrap_or_revert();
+    let balance = tags::balance(recipient, deposit_purse(recipient), tag);
+    runtime::ret(CLValue::from_t(balance).unwrap_or_revert());
+}
+
+// Opt-in for receiving deposits. Creates the deposit purse of the caller up front,
+// which is the only way to get one while `registration_required` is set.
+#[no_mangle]
+pub extern "C" fn register_recipient() {
+    let recipient = get_caller();
+    if !runtime::has_key(&recipient.to_string()) {
+        create_deposit_purse(recipient);
+    }
+}
+
+// Returns whether the recipient has a deposit purse in the contract, and so can be deposited to
+// even while `registration_required` is set.
+#[no_mangle]
+pub extern "C" fn is_registered() {
+    let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let recipient_account_str = recipient.into_account().unwrap_or_revert().to_string();
+    runtime::ret(CLValue::from_t(runtime::has_key(&recipient_account_str)).unwrap_or_revert());
+}
+
+// Owner only. Sets the lowest amount the `deposit` entry_point accepts.
+#[no_mangle]
+pub extern "C" fn set_minimum_deposit() {
+    require_owner();
+    let minimum_deposit: U512 = runtime::get_named_arg(MINIMUM_DEPOSIT);
+    write_named_value(MINIMUM_DEPOSIT, minimum_deposit);
+}
+
+// Owner only. Switches the opt-in mode, in which only registered recipients can receive deposits.
+#[no_mangle]
+pub extern "C" fn set_registration_required() {
+    require_owner();
+    let registration_required: bool = runtime::get_named_arg(REGISTRATION_REQUIRED);
+    write_named_value(REGISTRATION_REQUIRED, registration_required);
+}
+
+// Called once by `call` right after the contract is stored, to keep the hash of the contract in its own
+// named keys, so signed messages can be bound to this installation of the contract.
+#[no_mangle]
+pub extern "C" fn init() {
+    if runtime::has_key(DEPOSIT_CONTRACT_HASH) {
+        runtime::revert(Error::AlreadyInitialized);
+    }
+    let contract_hash: ContractHash = runtime::get_named_arg(DEPOSIT_CONTRACT_HASH);
+    runtime::put_key(
+        DEPOSIT_CONTRACT_HASH,
+        storage::new_uref(contract_hash).into(),
+    );
+}
+
 #[no_mangle]
 pub extern "C" fn call() {
+    let minimum_deposit: U512 = runtime::get_named_arg(MINIMUM_DEPOSIT);
+    let registration_required: bool = runtime::get_named_arg(REGISTRATION_REQUIRED);
+
     let mut entry_points = EntryPoints::new();
 
     entry_points.add_entry_point(EntryPoint::new(
@@ -94,6 +297,8 @@ pub extern "C" fn call() {
             Parameter::new(DEPOSIT_PURSE, URef::cl_type()),
             Parameter::new(DEPOSIT_RECIPIENT, Key::cl_type()),
             Parameter::new(AMOUNT, Option::<U512>::cl_type()),
+            Parameter::new(TRANSFER_ID, Option::<u64>::cl_type()),
+            Parameter::new(TAG, Option::<u64>::cl_type()),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
@@ -102,7 +307,25 @@ pub extern "C" fn call() {
 
     entry_points.add_entry_point(EntryPoint::new(
         COLLECT,
-        vec![Parameter::new(AMOUNT, Option::<U512>::cl_type())],
+        vec![
+            Parameter::new(AMOUNT, Option::<U512>::cl_type()),
+            Parameter::new(TARGET, Option::<AccountHash>::cl_type()),
+            Parameter::new(TRANSFER_ID, Option::<u64>::cl_type()),
+            Parameter::new(TAG, Option::<u64>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        COLLECT_TO_PURSE,
+        vec![
+            Parameter::new(TARGET_PURSE, URef::cl_type()),
+            Parameter::new(AMOUNT, Option::<U512>::cl_type()),
+            Parameter::new(TRANSFER_ID, Option::<u64>::cl_type()),
+            Parameter::new(TAG, Option::<u64>::cl_type()),
+        ],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -116,13 +339,113 @@ pub extern "C" fn call() {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        GET_DEPOSIT_BALANCE,
+        vec![
+            Parameter::new(DEPOSIT_RECIPIENT, Key::cl_type()),
+            Parameter::new(TAG, Option::<u64>::cl_type()),
+        ],
+        U512::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        REGISTER_RECIPIENT,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        IS_REGISTERED,
+        vec![Parameter::new(DEPOSIT_RECIPIENT, Key::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_MINIMUM_DEPOSIT,
+        vec![Parameter::new(MINIMUM_DEPOSIT, U512::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_REGISTRATION_REQUIRED,
+        vec![Parameter::new(REGISTRATION_REQUIRED, bool::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    withdrawal_address::add_entry_points(&mut entry_points);
+    escrow::add_entry_points(&mut entry_points);
+    milestones::add_entry_points(&mut entry_points);
+    subscriptions::add_entry_points(&mut entry_points);
+    scheduled_payments::add_entry_points(&mut entry_points);
+    airdrop::add_entry_points(&mut entry_points);
+    channels::add_entry_points(&mut entry_points);
+    cheques::add_entry_points(&mut entry_points);
+    campaigns::add_entry_points(&mut entry_points);
+    bills::add_entry_points(&mut entry_points);
+    rosca::add_entry_points(&mut entry_points);
+    bounties::add_entry_points(&mut entry_points);
+    matching::add_entry_points(&mut entry_points);
+    dead_man_switch::add_entry_points(&mut entry_points);
+    guardians::add_entry_points(&mut entry_points);
+    vault::add_entry_points(&mut entry_points);
+    limits::add_entry_points(&mut entry_points);
+    joint::add_entry_points(&mut entry_points);
+    treasury::add_entry_points(&mut entry_points);
+    goals::add_entry_points(&mut entry_points);
+
+    entry_points.add_entry_point(EntryPoint::new(
+        INIT,
+        vec![Parameter::new(
+            DEPOSIT_CONTRACT_HASH,
+            ContractHash::cl_type(),
+        )],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    // The installing account becomes the owner, who can change the deposit settings later on.
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        OWNER.to_string(),
+        storage::new_uref(runtime::get_caller()).into(),
+    );
+    named_keys.insert(
+        MINIMUM_DEPOSIT.to_string(),
+        storage::new_uref(minimum_deposit).into(),
+    );
+    named_keys.insert(
+        REGISTRATION_REQUIRED.to_string(),
+        storage::new_uref(registration_required).into(),
+    );
+    named_keys.insert(
+        KEEPER_TIP.to_string(),
+        storage::new_uref(U512::zero()).into(),
+    );
+
     let (contract_hash, _version) = storage::new_contract(
         entry_points,
-        None,
+        Some(named_keys),
         Some("deposit_contract_package".to_string()),
         Some("deposit_contract_access_token".to_string()),
     );
 
+    runtime::call_contract::<()>(
+        contract_hash,
+        INIT,
+        runtime_args! {DEPOSIT_CONTRACT_HASH => contract_hash},
+    );
+
     runtime::put_key("deposit_contract", contract_hash.into());
 
     // Added for the testing convinience.
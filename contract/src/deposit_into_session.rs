@@ -7,7 +7,7 @@ use casper_contract::{
 };
 use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, URef};
 mod constants;
-use constants::{AMOUNT, DEPOSIT_CONTRACT_HASH, DEPOSIT_RECIPIENT, GET_DEPOSIT_PURSE};
+use constants::{AMOUNT, DEPOSIT_CONTRACT_HASH, DEPOSIT_RECIPIENT, GET_DEPOSIT_PURSE, TRANSFER_ID};
 
 // Session code that executes in the callers context.
 // In this design we use a getter function to fetch a purse from the contract to deposit into.
@@ -19,6 +19,7 @@ pub extern "C" fn call() {
     let deposit_contract_hash: ContractHash = runtime::get_named_arg(DEPOSIT_CONTRACT_HASH);
     let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
     let amount = runtime::get_named_arg(AMOUNT);
+    let id: Option<u64> = runtime::get_named_arg(TRANSFER_ID);
     // Calling the deposit contract to get a URef to the deposit purse associated with the recipient
     let deposit_purse: URef = runtime::call_contract(
         deposit_contract_hash,
@@ -27,9 +28,9 @@ pub extern "C" fn call() {
             DEPOSIT_RECIPIENT => recipient
         },
     );
-    // We transfer the specified amount into the deposit purse of the recipient.
+    // We transfer the specified amount into the deposit purse of the recipient, with the optional transfer `id`.
     // As long as this function call does not fail the transfer of motes happens,
     // and there is no need to transfer the purse URef back to the contract.
-    system::transfer_from_purse_to_purse(account::get_main_purse(), deposit_purse, amount, None)
+    system::transfer_from_purse_to_purse(account::get_main_purse(), deposit_purse, amount, id)
         .unwrap_or_revert();
 }
@@ -6,7 +6,9 @@ use casper_contract::unwrap_or_revert::UnwrapOrRevert;
 use casper_types::Key;
 use casper_types::{runtime_args, ContractHash, RuntimeArgs};
 mod constants;
-use constants::{AMOUNT, DEPOSIT, DEPOSIT_CONTRACT_HASH, DEPOSIT_PURSE, DEPOSIT_RECIPIENT};
+use constants::{
+    AMOUNT, DEPOSIT, DEPOSIT_CONTRACT_HASH, DEPOSIT_PURSE, DEPOSIT_RECIPIENT, TAG, TRANSFER_ID,
+};
 
 // This constant can be replaced easily with a named argument in the session code.
 pub const MY_TRANSFER_PURSE: &str = "my_transfer_purse";
@@ -20,6 +22,7 @@ pub const MY_TRANSFER_PURSE: &str = "my_transfer_purse";
 pub extern "C" fn call() {
     let deposit_contract_hash: ContractHash = runtime::get_named_arg(DEPOSIT_CONTRACT_HASH);
     let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let tag: Option<u64> = runtime::get_named_arg(TAG);
     // Get transport purse from the named keys, or if it doesn't exist, create and store a new one.
     let transport_purse = match runtime::get_key(MY_TRANSFER_PURSE) {
         Some(purse_key) => purse_key.into_uref().unwrap_or_revert(),
@@ -30,8 +33,9 @@ pub extern "C" fn call() {
         }
     };
     let amount = runtime::get_named_arg(AMOUNT);
-    // Transfer motes to the transport purse
-    system::transfer_from_purse_to_purse(account::get_main_purse(), transport_purse, amount, None)
+    let id: Option<u64> = runtime::get_named_arg(TRANSFER_ID);
+    // Transfer motes to the transport purse. Both transfers of the deposit carry the same optional `id`.
+    system::transfer_from_purse_to_purse(account::get_main_purse(), transport_purse, amount, id)
         .unwrap_or_revert();
     // Use the purse as an argument for the contract entrypoint.
     // NOTE: for the callee to be able to withdraw from this purse, the URef needs READ and WRITE access bytes.
@@ -42,7 +46,9 @@ pub extern "C" fn call() {
         runtime_args! {
             DEPOSIT_RECIPIENT => recipient,
             DEPOSIT_PURSE => transport_purse,
-            AMOUNT => Some(amount)
+            AMOUNT => Some(amount),
+            TRANSFER_ID => id,
+            TAG => tag
         },
     );
 }
@@ -0,0 +1,251 @@
+use casper_types::ApiError;
+
+/// Errors the deposit contract reverts with. Each variant surfaces as `ApiError::User(code)`.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The caller has no deposit purse stored in the contract.
+    NoDepositPurse = 1,
+    /// The caller is not the account that installed the contract.
+    NotOwner = 2,
+    /// The deposited amount is lower than the configured minimum deposit.
+    BelowMinimumDeposit = 3,
+    /// Registration is required and the recipient has not called `register_recipient`.
+    RecipientNotRegistered = 4,
+    /// A withdrawal address is configured, so the deposit cannot be collected anywhere else.
+    WithdrawalAddressLocked = 5,
+    /// The deposit, or the part of it under the requested tag, holds less than the requested amount.
+    InsufficientBalance = 6,
+    /// There is no escrow with the given id.
+    EscrowNotFound = 7,
+    /// Only the payer of the escrow can do this.
+    NotPayer = 8,
+    /// Only the payee of the escrow can do this.
+    NotPayee = 9,
+    /// Only the arbiter of the escrow can do this.
+    NotArbiter = 10,
+    /// Only the payer or the payee of the escrow can do this.
+    NotEscrowParty = 11,
+    /// The escrow is no longer open, it has been disputed or closed already.
+    EscrowNotOpen = 12,
+    /// The escrow is not disputed, so there is nothing for the arbiter to settle.
+    EscrowNotDisputed = 13,
+    /// The arbiter tried to pay the payee more than the escrow holds.
+    SettlementExceedsEscrow = 14,
+    /// The arbiter of an escrow cannot be its payer or payee.
+    InvalidArbiter = 15,
+    /// The escrow has no milestone with the given index.
+    MilestoneNotFound = 16,
+    /// The milestone has been approved or cancelled already.
+    MilestoneNotPending = 17,
+    /// The escrow is not optimistic, or its challenge window has not passed yet.
+    EscrowNotClaimable = 18,
+    /// The challenge window of the optimistic escrow has passed, so it can no longer be disputed.
+    ChallengeWindowClosed = 19,
+    /// There is no subscription with the given id.
+    SubscriptionNotFound = 20,
+    /// The subscription has been cancelled by the subscriber, or ran out of funding.
+    SubscriptionCancelled = 21,
+    /// Only the merchant of the subscription can do this.
+    NotMerchant = 22,
+    /// Only the subscriber can do this.
+    NotSubscriber = 23,
+    /// The subscription has been charged for the current period already.
+    ChargeNotDue = 24,
+    /// The subscription period has to be longer than zero.
+    InvalidPeriod = 25,
+    /// There is no scheduled payment with the given id.
+    PaymentNotFound = 26,
+    /// The payment has been executed or cancelled already.
+    PaymentNotScheduled = 27,
+    /// None of the given payments could be executed.
+    NoPaymentDue = 28,
+    /// There is no distribution with the given id.
+    DistributionNotFound = 29,
+    /// The caller has claimed from this distribution already.
+    AlreadyClaimed = 30,
+    /// The Merkle proof does not lead from the (caller, amount) leaf to the root of the distribution.
+    InvalidProof = 31,
+    /// There is no payment channel with the given id.
+    ChannelNotFound = 32,
+    /// The payment channel has been reclaimed by the payer and closed.
+    ChannelClosed = 33,
+    /// The payment channel timed out, so it can no longer be redeemed from.
+    ChannelExpired = 34,
+    /// The payment channel has not timed out yet, so the payer cannot reclaim it.
+    ChannelNotExpired = 35,
+    /// The preimage does not hash to the head of the chain in the given number of links.
+    InvalidPreimage = 36,
+    /// `init` can only run once, when the contract is installed.
+    AlreadyInitialized = 37,
+    /// Only ed25519 keys can be registered for signing cheques.
+    InvalidPublicKey = 38,
+    /// The payer of the cheque has not registered a key for signing cheques.
+    NoChequeKey = 39,
+    /// The cheque is not signed by the key the payer registered, or was altered.
+    InvalidSignature = 40,
+    /// The expiry of the cheque has passed.
+    ChequeExpired = 41,
+    /// The nonce of the cheque has been redeemed already.
+    ChequeAlreadyRedeemed = 42,
+    /// There is no campaign with the given id.
+    CampaignNotFound = 43,
+    /// The campaign has been finalized or its deadline has passed.
+    CampaignEnded = 44,
+    /// The campaign has not raised its goal, so it cannot be finalized.
+    GoalNotMet = 45,
+    /// The campaign met its goal or is still running, so contributions cannot be refunded.
+    CampaignNotFailed = 46,
+    /// The caller has no contribution to refund.
+    NothingToRefund = 47,
+    /// There is no bill with the given id.
+    BillNotFound = 48,
+    /// The bill has been collected or cancelled already.
+    BillNotOpen = 49,
+    /// The bill expired, so shares can no longer be paid.
+    BillExpired = 50,
+    /// The caller is not one of the participants of the bill.
+    NotParticipant = 51,
+    /// The caller has paid their share of the bill already.
+    ShareAlreadyPaid = 52,
+    /// Not every participant has paid their share of the bill yet.
+    BillNotFullyPaid = 53,
+    /// Shares can only be withdrawn from cancelled bills, or expired bills that were not fully paid.
+    BillNotRefundable = 54,
+    /// There is no savings circle with the given id.
+    CircleNotFound = 55,
+    /// The caller is not a member of the savings circle.
+    NotMember = 56,
+    /// The caller exited the savings circle.
+    MemberExited = 57,
+    /// The caller has paid for the current round already.
+    RoundAlreadyPaid = 58,
+    /// Not every member has paid for the current round yet.
+    RoundNotFullyPaid = 59,
+    /// Every member has received the pot already.
+    CircleFinished = 60,
+    /// The caller received the pot already, so they cannot exit the savings circle.
+    PotAlreadyReceived = 61,
+    /// There is no bounty with the given id.
+    BountyNotFound = 62,
+    /// Only the poster of the bounty can do this.
+    NotPoster = 63,
+    /// The bounty has been awarded or refunded already.
+    BountyClosed = 64,
+    /// The bounty expired, so claims can no longer be submitted.
+    BountyExpired = 65,
+    /// The bounty has not expired yet, so the poster cannot take the reward back.
+    BountyNotExpired = 66,
+    /// The account has not submitted a claim on the bounty.
+    ClaimNotFound = 67,
+    /// There is no matching pool with the given id.
+    MatchingPoolNotFound = 68,
+    /// Only the sponsor of the matching pool can do this.
+    NotSponsor = 69,
+    /// The caller has not named a beneficiary.
+    NoBeneficiary = 70,
+    /// The caller is not the beneficiary the recipient named.
+    NotBeneficiary = 71,
+    /// The inactivity period of the recipient has not lapsed yet.
+    RecipientStillActive = 72,
+    /// The threshold has to be at least one, and at most the number of guardians or owners.
+    InvalidThreshold = 73,
+    /// The caller is not one of the guardians of the recipient.
+    NotGuardian = 74,
+    /// The caller has approved this already.
+    AlreadyApproved = 75,
+    /// There is no approved recovery of the deposit.
+    NoRecoveryPending = 76,
+    /// The delay of the approved recovery has not passed yet.
+    RecoveryNotReady = 77,
+    /// A recovery of the deposit has been approved already, and can only be vetoed or executed.
+    RecoveryPending = 78,
+    /// The deposit is in vault mode, so it can only be withdrawn by requesting and finalizing a withdrawal.
+    VaultLocked = 79,
+    /// The deposit is not in vault mode.
+    VaultNotEnabled = 80,
+    /// A withdrawal has been requested already, and has to be finalized or cancelled first.
+    WithdrawalPending = 81,
+    /// There is no requested withdrawal.
+    NoWithdrawalPending = 82,
+    /// The delay of the requested withdrawal has not passed yet.
+    WithdrawalNotReady = 83,
+    /// Taking the amount out of the deposit would exceed the daily limit of the recipient.
+    DailyLimitExceeded = 84,
+    /// There is no joint account with the given account hash.
+    JointAccountNotFound = 85,
+    /// The caller is not one of the owners of the joint account.
+    NotJointOwner = 86,
+    /// The account to add is an owner already, or the account to remove is none.
+    InvalidOwnerChange = 87,
+    /// There is no proposal with the given id.
+    ProposalNotFound = 88,
+    /// The proposal has been executed already.
+    ProposalExecuted = 89,
+    /// The owners of the joint account changed since the proposal was made.
+    ProposalOutdated = 90,
+    /// There is no treasury with the given account hash.
+    TreasuryNotFound = 91,
+    /// The caller is not one of the members of the treasury.
+    NotTreasuryMember = 92,
+    /// The quorum has to be at least one, and at most the total weight of the members.
+    InvalidQuorum = 93,
+    /// Voting on the proposal has ended.
+    VotingClosed = 94,
+    /// Voting on the proposal has not ended yet.
+    VotingNotEnded = 95,
+    /// The caller has voted on the proposal already.
+    AlreadyVoted = 96,
+    /// The proposal missed the quorum, or did not get more weight for it than against it.
+    ProposalRejected = 97,
+    /// A savings goal needs a target amount or an unlock time, and a penalty rate of at most 10000 basis points.
+    /// A penalty recipient has to be someone else than the saver, and get a penalty rate above zero.
+    InvalidGoal = 98,
+    /// The caller has a savings goal with the given name already.
+    GoalExists = 99,
+    /// There is no savings goal with the given name.
+    GoalNotFound = 100,
+    /// The savings goal has not reached its target or unlock time, and has no penalty recipient.
+    GoalLocked = 101,
+    /// The deadline of the distribution has passed, so it can no longer be claimed from.
+    DistributionExpired = 102,
+    /// The deadline of the distribution has not passed yet, so the funder cannot reclaim it.
+    DistributionNotExpired = 103,
+    /// The caller did not fund the distribution.
+    NotFunder = 104,
+    /// A bill needs at least one participant, each listed once with a share above zero.
+    InvalidBill = 105,
+    /// A savings circle needs at least one member, each listed once, a contribution above zero,
+    /// and rounds longer than zero.
+    InvalidCircle = 106,
+    /// The current round of the savings circle is not overdue yet, so nobody can be marked as defaulted.
+    RoundNotOverdue = 107,
+    /// The poster of the bounty cannot submit a claim on it, so they cannot award the reward to themselves.
+    PosterCannotClaim = 108,
+    /// The ratio of a matching pool has to be above zero and at most `MAX_MATCHING_RATIO` basis points.
+    InvalidRatio = 109,
+    /// The charity has `MAX_MATCHING_POOLS` matching pools already.
+    TooManyMatchingPools = 110,
+    /// The inactivity period of a dead man's switch has to be at least `MIN_INACTIVITY_PERIOD`.
+    InvalidInactivityPeriod = 111,
+    /// The owners of a joint account have to be listed once each.
+    DuplicateOwner = 112,
+    /// The members of a treasury have to be listed once each, with weights that add up to at most `u64::MAX`.
+    InvalidMembers = 113,
+    /// A milestone escrow needs at least one milestone, each with an amount above zero.
+    InvalidMilestones = 114,
+    /// The amount charged per period of a subscription has to be above zero.
+    InvalidSubscriptionAmount = 115,
+    /// The value of a link of a payment channel has to be above zero.
+    InvalidLinkValue = 116,
+    /// A matching pool has to be funded with at least `MIN_MATCHING_POOL` motes.
+    MatchingPoolTooSmall = 117,
+    /// The guardians of a deposit have to be listed once each.
+    DuplicateGuardian = 118,
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        ApiError::User(error as u16)
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::{string::ToString, vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, ARBITER, CHALLENGE_WINDOW, CLAIM_ESCROW, DISPUTE_ESCROW, ESCROWS, ESCROW_COUNT,
+        ESCROW_ID, ESCROW_PURSE, OPEN_ESCROW, OPEN_OPTIMISTIC_ESCROW, OWNER, PAYEE, PAYEE_AMOUNT,
+        REFUND_ESCROW, RELEASE_ESCROW, SETTLE_ESCROW,
+    },
+    credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{
+        current_time, get_dictionary_value, named_purse, next_id, read_named_value,
+        set_dictionary_value,
+    },
+};
+
+// Escrows lock motes taken from the deposit of the payer in the escrow purse of the contract.
+// Open -> Released:  the payer releases the motes to the payee.
+// Open -> Refunded:  the payee gives the motes back to the payer.
+// Open -> Disputed:  either side raises a dispute, after which only the arbiter can act.
+// Disputed -> Settled: the arbiter splits the motes between the payee and the payer.
+// Released and refunded motes end up in the deposit of the receiving side, from where they can be collected.
+// Optimistic escrows are arbitrated by the contract owner and carry a `release_at` block time:
+// Open -> Released:  once `release_at` has passed, the payee claims the motes without the payer.
+// Open -> Disputed:  only the payer, and only before `release_at`, the challenge window of the payer.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EscrowStatus {
+    Open,
+    Disputed,
+    Released,
+    Refunded,
+    Settled,
+}
+
+stored_enum!(EscrowStatus {
+    Open,
+    Disputed,
+    Released,
+    Refunded,
+    Settled,
+});
+
+struct Escrow {
+    payer: AccountHash,
+    payee: AccountHash,
+    arbiter: AccountHash,
+    amount: U512,
+    status: EscrowStatus,
+    release_at: Option<u64>,
+}
+
+stored_struct!(Escrow {
+    payer,
+    payee,
+    arbiter,
+    amount,
+    status,
+    release_at,
+});
+
+fn read_escrow(escrow_id: u64) -> Escrow {
+    get_dictionary_value(ESCROWS, &escrow_id.to_string())
+        .unwrap_or_revert_with(Error::EscrowNotFound)
+}
+
+fn write_escrow(escrow_id: u64, escrow: Escrow) {
+    set_dictionary_value(ESCROWS, &escrow_id.to_string(), escrow);
+}
+
+// Reads the escrow passed in as `escrow_id`, reverting unless it has the expected status.
+fn escrow_arg(expected: EscrowStatus) -> (u64, Escrow) {
+    let escrow_id: u64 = runtime::get_named_arg(ESCROW_ID);
+    let escrow = read_escrow(escrow_id);
+    if escrow.status != expected {
+        match expected {
+            EscrowStatus::Disputed => runtime::revert(Error::EscrowNotDisputed),
+            _ => runtime::revert(Error::EscrowNotOpen),
+        }
+    }
+    (escrow_id, escrow)
+}
+
+// Opens an escrow for `amount` out of the callers deposit, and returns its id.
+// The payee is checked up front, so that releasing cannot fail later on for a recipient that is not registered.
+fn open(arbiter: AccountHash, release_at: Option<u64>) {
+    let payer = runtime::get_caller();
+    let payee: AccountHash = runtime::get_named_arg(PAYEE);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    if arbiter == payer || arbiter == payee {
+        runtime::revert(Error::InvalidArbiter);
+    }
+    get_or_create_deposit_purse(payee);
+    spend_deposit(payer, named_purse(ESCROW_PURSE), amount);
+    let escrow_id = next_id(ESCROW_COUNT);
+    write_escrow(
+        escrow_id,
+        Escrow {
+            payer,
+            payee,
+            arbiter,
+            amount,
+            status: EscrowStatus::Open,
+            release_at,
+        },
+    );
+    runtime::ret(CLValue::from_t(escrow_id).unwrap_or_revert());
+}
+
+// Opens an escrow that `arbiter` settles in case of a dispute.
+#[no_mangle]
+pub extern "C" fn open_escrow() {
+    open(runtime::get_named_arg(ARBITER), None);
+}
+
+// Opens an optimistic escrow, which the payee can claim once `challenge_window` (in milliseconds
+// of block time) has passed, unless the payer disputes it before. Disputes are settled by the contract owner.
+// A window too long to end before `u64::MAX` never ends, so the payee cannot claim such an escrow at all.
+#[no_mangle]
+pub extern "C" fn open_optimistic_escrow() {
+    let challenge_window: u64 = runtime::get_named_arg(CHALLENGE_WINDOW);
+    open(
+        read_named_value(OWNER),
+        Some(current_time().saturating_add(challenge_window)),
+    );
+}
+
+// Payee only. Claims an open optimistic escrow whose challenge window has passed.
+#[no_mangle]
+pub extern "C" fn claim_escrow() {
+    let (escrow_id, mut escrow) = escrow_arg(EscrowStatus::Open);
+    if runtime::get_caller() != escrow.payee {
+        runtime::revert(Error::NotPayee);
+    }
+    match escrow.release_at {
+        Some(release_at) if release_at <= current_time() => {}
+        _ => runtime::revert(Error::EscrowNotClaimable),
+    }
+    credit_deposit(named_purse(ESCROW_PURSE), escrow.payee, escrow.amount);
+    escrow.status = EscrowStatus::Released;
+    write_escrow(escrow_id, escrow);
+}
+
+// Payer only. Releases an open escrow to the payee.
+#[no_mangle]
+pub extern "C" fn release_escrow() {
+    let (escrow_id, mut escrow) = escrow_arg(EscrowStatus::Open);
+    if runtime::get_caller() != escrow.payer {
+        runtime::revert(Error::NotPayer);
+    }
+    credit_deposit(named_purse(ESCROW_PURSE), escrow.payee, escrow.amount);
+    escrow.status = EscrowStatus::Released;
+    write_escrow(escrow_id, escrow);
+}
+
+// Payee only. Refunds an open escrow to the payer.
+#[no_mangle]
+pub extern "C" fn refund_escrow() {
+    let (escrow_id, mut escrow) = escrow_arg(EscrowStatus::Open);
+    if runtime::get_caller() != escrow.payee {
+        runtime::revert(Error::NotPayee);
+    }
+    credit_deposit(named_purse(ESCROW_PURSE), escrow.payer, escrow.amount);
+    escrow.status = EscrowStatus::Refunded;
+    write_escrow(escrow_id, escrow);
+}
+
+// Payer or payee only. Freezes an open escrow until the arbiter settles it.
+// Optimistic escrows can only be disputed by the payer, and only within their challenge window.
+#[no_mangle]
+pub extern "C" fn dispute_escrow() {
+    let (escrow_id, mut escrow) = escrow_arg(EscrowStatus::Open);
+    let caller = runtime::get_caller();
+    if caller != escrow.payer && caller != escrow.payee {
+        runtime::revert(Error::NotEscrowParty);
+    }
+    if let Some(release_at) = escrow.release_at {
+        if caller != escrow.payer {
+            runtime::revert(Error::NotPayer);
+        }
+        if release_at <= current_time() {
+            runtime::revert(Error::ChallengeWindowClosed);
+        }
+    }
+    escrow.status = EscrowStatus::Disputed;
+    write_escrow(escrow_id, escrow);
+}
+
+// Arbiter only. Settles a disputed escrow by paying `payee_amount` to the payee and the rest back to the payer.
+#[no_mangle]
+pub extern "C" fn settle_escrow() {
+    let (escrow_id, mut escrow) = escrow_arg(EscrowStatus::Disputed);
+    if runtime::get_caller() != escrow.arbiter {
+        runtime::revert(Error::NotArbiter);
+    }
+    let payee_amount: U512 = runtime::get_named_arg(PAYEE_AMOUNT);
+    if payee_amount > escrow.amount {
+        runtime::revert(Error::SettlementExceedsEscrow);
+    }
+    let escrow_purse = named_purse(ESCROW_PURSE);
+    if !payee_amount.is_zero() {
+        credit_deposit(escrow_purse, escrow.payee, payee_amount);
+    }
+    if payee_amount < escrow.amount {
+        credit_deposit(escrow_purse, escrow.payer, escrow.amount - payee_amount);
+    }
+    escrow.status = EscrowStatus::Settled;
+    write_escrow(escrow_id, escrow);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        OPEN_ESCROW,
+        vec![
+            Parameter::new(PAYEE, AccountHash::cl_type()),
+            Parameter::new(ARBITER, AccountHash::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        OPEN_OPTIMISTIC_ESCROW,
+        vec![
+            Parameter::new(PAYEE, AccountHash::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(CHALLENGE_WINDOW, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [RELEASE_ESCROW, REFUND_ESCROW, DISPUTE_ESCROW, CLAIM_ESCROW] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![Parameter::new(ESCROW_ID, u64::cl_type())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        SETTLE_ESCROW,
+        vec![
+            Parameter::new(ESCROW_ID, u64::cl_type()),
+            Parameter::new(PAYEE_AMOUNT, U512::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
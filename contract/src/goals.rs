@@ -0,0 +1,202 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CREATE_GOAL, DEPOSIT_RECIPIENT, GET_GOAL_BALANCE, GOAL_NAME, PENALTY_RATE,
+        PENALTY_RECIPIENT, SAVE_TO_GOAL, SAVINGS_GOALS, SAVINGS_GOAL_PURSE, TARGET_AMOUNT,
+        UNLOCK_AT, WITHDRAW_GOAL,
+    },
+    credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, named_purse, set_dictionary_value,
+    },
+};
+
+// Savings goals lock money the saver moves out of their own deposit under a name of their choosing.
+// A goal unlocks once its balance reaches `target_amount`, or once the block time reaches `unlock_at`,
+// whichever of the two is configured and comes first. Withdrawing credits the whole balance back to the
+// deposit of the saver and closes the goal, so its name can be used again. Before the goal unlocks,
+// withdrawing is only possible if the goal has a `penalty_recipient`, whose deposit receives
+// `penalty_rate` basis points of the balance.
+struct SavingsGoal {
+    balance: U512,
+    target_amount: Option<U512>,
+    unlock_at: Option<u64>,
+    penalty_rate: u32,
+    penalty_recipient: Option<AccountHash>,
+}
+
+stored_struct!(SavingsGoal {
+    balance,
+    target_amount,
+    unlock_at,
+    penalty_rate,
+    penalty_recipient,
+});
+
+impl SavingsGoal {
+    fn unlocked(&self) -> bool {
+        self.target_amount
+            .map_or(false, |target_amount| self.balance >= target_amount)
+            || self
+                .unlock_at
+                .map_or(false, |unlock_at| current_time() >= unlock_at)
+    }
+}
+
+fn savings_goal(saver: AccountHash, name: &str) -> Option<SavingsGoal> {
+    get_dictionary_value::<Option<SavingsGoal>>(
+        SAVINGS_GOALS,
+        &dictionary_key(&(saver, name.to_string())),
+    )
+    .flatten()
+}
+
+fn write_savings_goal(saver: AccountHash, name: &str, goal: Option<SavingsGoal>) {
+    set_dictionary_value(
+        SAVINGS_GOALS,
+        &dictionary_key(&(saver, name.to_string())),
+        goal,
+    );
+}
+
+// Reads the goal of the caller named `goal_name`.
+fn goal_of_caller() -> (AccountHash, String, SavingsGoal) {
+    let saver = runtime::get_caller();
+    let name: String = runtime::get_named_arg(GOAL_NAME);
+    let goal = savings_goal(saver, &name).unwrap_or_revert_with(Error::GoalNotFound);
+    (saver, name, goal)
+}
+
+// Creates a goal of the caller named `goal_name`, which needs a target amount, an unlock time, or both.
+#[no_mangle]
+pub extern "C" fn create_goal() {
+    let saver = runtime::get_caller();
+    let name: String = runtime::get_named_arg(GOAL_NAME);
+    let target_amount: Option<U512> = runtime::get_named_arg(TARGET_AMOUNT);
+    let unlock_at: Option<u64> = runtime::get_named_arg(UNLOCK_AT);
+    let penalty_rate: u32 = runtime::get_named_arg(PENALTY_RATE);
+    let penalty_recipient: Option<AccountHash> = runtime::get_named_arg(PENALTY_RECIPIENT);
+    if (target_amount.is_none() && unlock_at.is_none()) || penalty_rate > 10_000 {
+        runtime::revert(Error::InvalidGoal);
+    }
+    // An early withdrawal pays the penalty to someone else, so it has to cost the saver something.
+    if penalty_recipient.is_some() && (penalty_recipient == Some(saver) || penalty_rate == 0) {
+        runtime::revert(Error::InvalidGoal);
+    }
+    if savings_goal(saver, &name).is_some() {
+        runtime::revert(Error::GoalExists);
+    }
+    if let Some(penalty_recipient) = penalty_recipient {
+        get_or_create_deposit_purse(penalty_recipient);
+    }
+    write_savings_goal(
+        saver,
+        &name,
+        Some(SavingsGoal {
+            balance: U512::zero(),
+            target_amount,
+            unlock_at,
+            penalty_rate,
+            penalty_recipient,
+        }),
+    );
+}
+
+// Moves `amount` out of the deposit of the caller into their goal named `goal_name`.
+#[no_mangle]
+pub extern "C" fn save_to_goal() {
+    let (saver, name, mut goal) = goal_of_caller();
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    spend_deposit(saver, named_purse(SAVINGS_GOAL_PURSE), amount);
+    goal.balance += amount;
+    write_savings_goal(saver, &name, Some(goal));
+}
+
+// Closes the goal of the caller named `goal_name`, crediting its balance back to their deposit,
+// less the penalty if the goal is still locked.
+#[no_mangle]
+pub extern "C" fn withdraw_goal() {
+    let (saver, name, goal) = goal_of_caller();
+    let purse = named_purse(SAVINGS_GOAL_PURSE);
+    let mut payout = goal.balance;
+    if !goal.unlocked() {
+        let penalty_recipient = goal
+            .penalty_recipient
+            .unwrap_or_revert_with(Error::GoalLocked);
+        let penalty = goal.balance * U512::from(goal.penalty_rate) / U512::from(10_000);
+        if !penalty.is_zero() {
+            credit_deposit(purse, penalty_recipient, penalty);
+        }
+        payout -= penalty;
+    }
+    if !payout.is_zero() {
+        credit_deposit(purse, saver, payout);
+    }
+    write_savings_goal(saver, &name, None);
+}
+
+// Returns the balance of the goal of `recipient` named `goal_name`.
+#[no_mangle]
+pub extern "C" fn get_goal_balance() {
+    let saver: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let name: String = runtime::get_named_arg(GOAL_NAME);
+    let goal = savings_goal(saver, &name).unwrap_or_revert_with(Error::GoalNotFound);
+    runtime::ret(CLValue::from_t(goal.balance).unwrap_or_revert());
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_GOAL,
+        vec![
+            Parameter::new(GOAL_NAME, String::cl_type()),
+            Parameter::new(TARGET_AMOUNT, Option::<U512>::cl_type()),
+            Parameter::new(UNLOCK_AT, Option::<u64>::cl_type()),
+            Parameter::new(PENALTY_RATE, u32::cl_type()),
+            Parameter::new(PENALTY_RECIPIENT, Option::<AccountHash>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        SAVE_TO_GOAL,
+        vec![
+            Parameter::new(GOAL_NAME, String::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        WITHDRAW_GOAL,
+        vec![Parameter::new(GOAL_NAME, String::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        GET_GOAL_BALANCE,
+        vec![
+            Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type()),
+            Parameter::new(GOAL_NAME, String::cl_type()),
+        ],
+        U512::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
@@ -0,0 +1,266 @@
+use alloc::{vec, vec::Vec};
+use casper_contract::{
+    contract_api::{
+        runtime,
+        system::{self, transfer_from_purse_to_purse},
+    },
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter,
+};
+
+use crate::{
+    change_delay,
+    constants::{
+        APPROVE_RECOVERY, DEPOSIT_RECIPIENT, EXECUTE_RECOVERY, GUARDIANS, GUARDIAN_CONFIGS,
+        NEW_ACCOUNT, RECOVERY_APPROVALS, RECOVERY_DELAY, SET_GUARDIANS, THRESHOLD, VETO_RECOVERY,
+    },
+    deposit_purse,
+    error::Error,
+    get_or_create_deposit_purse, limits, tags,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, has_duplicates, set_dictionary_value,
+    },
+    vault,
+    withdrawal_address::withdrawal_address,
+};
+
+// Guardians can recover the deposit of a recipient who lost their key. Once `threshold` of the guardians
+// approved moving the deposit to the same new account, the recovery can be executed after `recovery_delay`
+// milliseconds of block time, during which the recipient can still veto it with their original key.
+// Approvals are counted per `nonce`, which moves on whenever the guardians change or a recovery is vetoed
+// or executed, so earlier approvals cannot be reused.
+// While the deposit is in vault mode or has a daily limit, new guardians only take effect after the `change_delay`,
+// so a stolen key cannot register its own guardians to get around either. Recoveries also wait out the withdrawal
+// delay of a vault, and count against the daily limit. A recipient who lost their key can still be recovered when
+// they configured a withdrawal address: as that address pins where the deposit can go, it is paid there instead.
+struct GuardianConfig {
+    guardians: Vec<AccountHash>,
+    threshold: u32,
+    recovery_delay: u64,
+    nonce: u64,
+    // The new account of an approved recovery, and the block time it can be executed at.
+    pending: Option<(AccountHash, u64)>,
+    // Guardians, threshold and recovery delay waiting for the change delay, and the block time they take effect at.
+    pending_guardians: Option<((Vec<AccountHash>, u32, u64), u64)>,
+}
+
+stored_struct!(GuardianConfig {
+    guardians,
+    threshold,
+    recovery_delay,
+    nonce,
+    pending,
+    pending_guardians,
+});
+
+impl GuardianConfig {
+    // Replaces the guardians, dropping any recovery the earlier ones started.
+    fn replace_guardians(
+        &mut self,
+        guardians: Vec<AccountHash>,
+        threshold: u32,
+        recovery_delay: u64,
+    ) {
+        self.guardians = guardians;
+        self.threshold = threshold;
+        self.recovery_delay = recovery_delay;
+        self.nonce += 1;
+        self.pending = None;
+        self.pending_guardians = None;
+    }
+
+    // Applies pending guardians once their delay has passed.
+    fn settle(&mut self) {
+        if let Some(((guardians, threshold, recovery_delay), effective_at)) =
+            self.pending_guardians.clone()
+        {
+            if effective_at <= current_time() {
+                self.replace_guardians(guardians, threshold, recovery_delay);
+            }
+        }
+    }
+}
+
+fn guardian_config(recipient: AccountHash) -> Option<GuardianConfig> {
+    get_dictionary_value(GUARDIAN_CONFIGS, &dictionary_key(&recipient)).map(
+        |mut config: GuardianConfig| {
+            config.settle();
+            config
+        },
+    )
+}
+
+fn set_guardian_config(recipient: AccountHash, config: GuardianConfig) {
+    set_dictionary_value(GUARDIAN_CONFIGS, &dictionary_key(&recipient), config);
+}
+
+// Whether `account` is one of the guardians the recipient registered.
+pub fn is_guardian(recipient: AccountHash, account: AccountHash) -> bool {
+    guardian_config(recipient).map_or(false, |config| config.guardians.contains(&account))
+}
+
+// Drops the pending recovery of the recipient along with all approvals given so far.
+fn reset_recovery(recipient: AccountHash, mut config: GuardianConfig) {
+    config.pending = None;
+    config.nonce += 1;
+    set_guardian_config(recipient, config);
+}
+
+// Registers the guardians of the callers deposit, any `threshold` of whom can recover it,
+// replacing the earlier ones and dropping any recovery they started. While the deposit is in vault mode
+// or has a daily limit, this only happens after the change delay, and the earlier guardians stay in place
+// until then.
+#[no_mangle]
+pub extern "C" fn set_guardians() {
+    let recipient = runtime::get_caller();
+    let guardians: Vec<AccountHash> = runtime::get_named_arg(GUARDIANS);
+    let threshold: u32 = runtime::get_named_arg(THRESHOLD);
+    let recovery_delay: u64 = runtime::get_named_arg(RECOVERY_DELAY);
+    // A guardian listed twice could only approve once, so the threshold might never be met.
+    if has_duplicates(&guardians) {
+        runtime::revert(Error::DuplicateGuardian);
+    }
+    if threshold == 0 || threshold as usize > guardians.len() {
+        runtime::revert(Error::InvalidThreshold);
+    }
+    let mut config = guardian_config(recipient).unwrap_or(GuardianConfig {
+        guardians: Vec::new(),
+        threshold: 0,
+        recovery_delay: 0,
+        nonce: 0,
+        pending: None,
+        pending_guardians: None,
+    });
+    let delay = change_delay(recipient);
+    if delay == 0 {
+        config.replace_guardians(guardians, threshold, recovery_delay);
+    } else {
+        config.pending_guardians = Some((
+            (guardians, threshold, recovery_delay),
+            current_time().saturating_add(delay),
+        ));
+    }
+    set_guardian_config(recipient, config);
+}
+
+// Guardian only. Approves moving the deposit of `recipient` to `new_account`. The approval that reaches
+// the threshold starts the delay, after which the recovery can be executed. In vault mode, the delay is
+// at least the withdrawal delay of the vault.
+#[no_mangle]
+pub extern "C" fn approve_recovery() {
+    let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let new_account: AccountHash = runtime::get_named_arg(NEW_ACCOUNT);
+    let guardian = runtime::get_caller();
+    let mut config = match guardian_config(recipient) {
+        Some(config) if config.guardians.contains(&guardian) => config,
+        _ => runtime::revert(Error::NotGuardian),
+    };
+    if config.pending.is_some() {
+        runtime::revert(Error::RecoveryPending);
+    }
+    let approvals_key = dictionary_key(&(recipient, config.nonce, new_account));
+    let mut approvals: Vec<AccountHash> =
+        get_dictionary_value(RECOVERY_APPROVALS, &approvals_key).unwrap_or_default();
+    if approvals.contains(&guardian) {
+        runtime::revert(Error::AlreadyApproved);
+    }
+    approvals.push(guardian);
+    if approvals.len() >= config.threshold as usize {
+        let delay = config.recovery_delay.max(vault::current_delay(recipient));
+        config.pending = Some((new_account, current_time().saturating_add(delay)));
+        set_guardian_config(recipient, config);
+    }
+    set_dictionary_value(RECOVERY_APPROVALS, &approvals_key, approvals);
+}
+
+// Vetoes the pending recovery of the callers deposit, discarding the approvals of the guardians.
+#[no_mangle]
+pub extern "C" fn veto_recovery() {
+    let recipient = runtime::get_caller();
+    let config = guardian_config(recipient)
+        .filter(|config| config.pending.is_some())
+        .unwrap_or_revert_with(Error::NoRecoveryPending);
+    reset_recovery(recipient, config);
+}
+
+// Moves the whole deposit of `recipient` into the deposit of the new account, once the delay of the
+// approved recovery has passed. A withdrawal address pins where a deposit can go, so a deposit with
+// one configured is transferred to that address instead, which the recipient can still reach.
+// With a daily limit, only what is left of the allowance is moved, and the recovery stays pending
+// until the rest is moved on the following days.
+#[no_mangle]
+pub extern "C" fn execute_recovery() {
+    let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let config = guardian_config(recipient).unwrap_or_revert_with(Error::NoRecoveryPending);
+    let (new_account, executable_at) = config
+        .pending
+        .unwrap_or_revert_with(Error::NoRecoveryPending);
+    if current_time() < executable_at {
+        runtime::revert(Error::RecoveryNotReady);
+    }
+    tags::clear(recipient);
+    let deposit_purse = deposit_purse(recipient);
+    let balance = tags::balance(recipient, deposit_purse, None);
+    let amount = limits::record_withdrawal_up_to(recipient, balance);
+    tags::debit(recipient, deposit_purse, amount, None);
+    match withdrawal_address(recipient) {
+        Some(address) => {
+            system::transfer_from_purse_to_account(deposit_purse, address, amount, None)
+                .unwrap_or_revert();
+        }
+        None => transfer_from_purse_to_purse(
+            deposit_purse,
+            get_or_create_deposit_purse(new_account),
+            amount,
+            None,
+        )
+        .unwrap_or_revert(),
+    }
+    if amount == balance {
+        reset_recovery(recipient, config);
+    }
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_GUARDIANS,
+        vec![
+            Parameter::new(GUARDIANS, Vec::<AccountHash>::cl_type()),
+            Parameter::new(THRESHOLD, u32::cl_type()),
+            Parameter::new(RECOVERY_DELAY, u64::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        APPROVE_RECOVERY,
+        vec![
+            Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type()),
+            Parameter::new(NEW_ACCOUNT, AccountHash::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        VETO_RECOVERY,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        EXECUTE_RECOVERY,
+        vec![Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
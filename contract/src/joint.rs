@@ -0,0 +1,298 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, bytesrepr::ToBytes, CLType, CLTyped, CLValue, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, APPROVE_JOINT_PROPOSAL, CREATE_JOINT_ACCOUNT, JOINT_ACCOUNT, JOINT_ACCOUNTS,
+        JOINT_ACCOUNT_COUNT, JOINT_PROPOSALS, JOINT_PROPOSAL_COUNT, OWNER, OWNERS, PROPOSAL_ID,
+        PROPOSE_ADD_OWNER, PROPOSE_JOINT_COLLECT, PROPOSE_REMOVE_OWNER, TARGET, THRESHOLD,
+    },
+    deposit_purse,
+    error::Error,
+    get_or_create_deposit_purse, tags,
+    utils::{dictionary_key, get_dictionary_value, has_duplicates, next_id, set_dictionary_value},
+};
+
+// Joint accounts are deposit slots owned by several accounts. Each one gets an account hash of its own,
+// derived from the blake2b hash of its id, which nobody holds a key for, so it is deposited to like any
+// other recipient. Collecting from it and changing its owners go through proposals, which execute once
+// `threshold` of the owners approved them, the proposing owner included. A threshold of 1 lets any single
+// owner act on their own, one equal to the number of owners requires all of them.
+// Proposals are bound to the `nonce` of the owners, which moves on with every change of the owners,
+// so proposals made before a change can no longer be approved. A change of the owners also sets the
+// threshold the owners agreed on for the owners after it.
+struct JointAccount {
+    owners: Vec<AccountHash>,
+    threshold: u32,
+    nonce: u64,
+}
+
+stored_struct!(JointAccount {
+    owners,
+    threshold,
+    nonce,
+});
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum JointAction {
+    Collect,
+    AddOwner,
+    RemoveOwner,
+}
+
+stored_enum!(JointAction {
+    Collect,
+    AddOwner,
+    RemoveOwner,
+});
+
+// A proposal to collect `amount` to the main purse of `account`, or to add or remove `account` as an owner
+// and have `threshold` of the owners act on the joint account from then on.
+struct JointProposal {
+    joint_account: AccountHash,
+    nonce: u64,
+    action: JointAction,
+    account: AccountHash,
+    amount: U512,
+    threshold: u32,
+    approvals: Vec<AccountHash>,
+    executed: bool,
+}
+
+stored_struct!(JointProposal {
+    joint_account,
+    nonce,
+    action,
+    account,
+    amount,
+    threshold,
+    approvals,
+    executed,
+});
+
+fn joint_account(joint_account: AccountHash) -> JointAccount {
+    get_dictionary_value(JOINT_ACCOUNTS, &dictionary_key(&joint_account))
+        .unwrap_or_revert_with(Error::JointAccountNotFound)
+}
+
+fn write_joint_account(joint_account: AccountHash, account: JointAccount) {
+    set_dictionary_value(JOINT_ACCOUNTS, &dictionary_key(&joint_account), account);
+}
+
+fn write_proposal(proposal_id: u64, proposal: JointProposal) {
+    set_dictionary_value(JOINT_PROPOSALS, &proposal_id.to_string(), proposal);
+}
+
+fn check_threshold(threshold: u32, owners: &[AccountHash]) {
+    if threshold == 0 || threshold as usize > owners.len() {
+        runtime::revert(Error::InvalidThreshold);
+    }
+}
+
+// Executes the proposal on the joint account.
+fn execute(proposal: &JointProposal, mut account: JointAccount) {
+    match proposal.action {
+        JointAction::Collect => {
+            // A joint account cannot name a tag, so whatever was deposited to it under one counts as untagged.
+            tags::clear(proposal.joint_account);
+            let deposit_purse = deposit_purse(proposal.joint_account);
+            tags::debit(proposal.joint_account, deposit_purse, proposal.amount, None);
+            system::transfer_from_purse_to_account(
+                deposit_purse,
+                proposal.account,
+                proposal.amount,
+                None,
+            )
+            .unwrap_or_revert();
+        }
+        JointAction::AddOwner => {
+            if account.owners.contains(&proposal.account) {
+                runtime::revert(Error::InvalidOwnerChange);
+            }
+            account.owners.push(proposal.account);
+            check_threshold(proposal.threshold, &account.owners);
+            account.threshold = proposal.threshold;
+            account.nonce += 1;
+            write_joint_account(proposal.joint_account, account);
+        }
+        JointAction::RemoveOwner => {
+            if !account.owners.contains(&proposal.account) {
+                runtime::revert(Error::InvalidOwnerChange);
+            }
+            account.owners.retain(|owner| *owner != proposal.account);
+            check_threshold(proposal.threshold, &account.owners);
+            account.threshold = proposal.threshold;
+            account.nonce += 1;
+            write_joint_account(proposal.joint_account, account);
+        }
+    }
+}
+
+// Owner only. Records the approval of the caller, executing the proposal once it reaches the threshold.
+fn approve(proposal_id: u64, mut proposal: JointProposal) {
+    let account = joint_account(proposal.joint_account);
+    let owner = runtime::get_caller();
+    if !account.owners.contains(&owner) {
+        runtime::revert(Error::NotJointOwner);
+    }
+    if proposal.executed {
+        runtime::revert(Error::ProposalExecuted);
+    }
+    if proposal.nonce != account.nonce {
+        runtime::revert(Error::ProposalOutdated);
+    }
+    if proposal.approvals.contains(&owner) {
+        runtime::revert(Error::AlreadyApproved);
+    }
+    proposal.approvals.push(owner);
+    if proposal.approvals.len() >= account.threshold as usize {
+        execute(&proposal, account);
+        proposal.executed = true;
+    }
+    write_proposal(proposal_id, proposal);
+}
+
+// Owner only. Proposes `action` on the joint account passed in as `joint_account`, approved by the caller
+// right away, and returns the id of the proposal.
+fn propose(action: JointAction, account: AccountHash, amount: U512, threshold: Option<u32>) {
+    let joint_account_hash: AccountHash = runtime::get_named_arg(JOINT_ACCOUNT);
+    let current = joint_account(joint_account_hash);
+    let proposal_id = next_id(JOINT_PROPOSAL_COUNT);
+    approve(
+        proposal_id,
+        JointProposal {
+            joint_account: joint_account_hash,
+            nonce: current.nonce,
+            action,
+            account,
+            amount,
+            threshold: threshold.unwrap_or(current.threshold),
+            approvals: Vec::new(),
+            executed: false,
+        },
+    );
+    runtime::ret(CLValue::from_t(proposal_id).unwrap_or_revert());
+}
+
+// Creates a joint account of `owners`, any `threshold` of whom can act on it, and returns its account hash.
+// Every owner is listed once, so the threshold counts distinct owners.
+#[no_mangle]
+pub extern "C" fn create_joint_account() {
+    let owners: Vec<AccountHash> = runtime::get_named_arg(OWNERS);
+    let threshold: u32 = runtime::get_named_arg(THRESHOLD);
+    if has_duplicates(&owners) {
+        runtime::revert(Error::DuplicateOwner);
+    }
+    check_threshold(threshold, &owners);
+    let id = next_id(JOINT_ACCOUNT_COUNT);
+    let joint_account_hash = AccountHash::new(runtime::blake2b(
+        (JOINT_ACCOUNTS.to_string(), id)
+            .to_bytes()
+            .unwrap_or_revert(),
+    ));
+    get_or_create_deposit_purse(joint_account_hash);
+    write_joint_account(
+        joint_account_hash,
+        JointAccount {
+            owners,
+            threshold,
+            nonce: 0,
+        },
+    );
+    runtime::ret(CLValue::from_t(joint_account_hash).unwrap_or_revert());
+}
+
+// Owner only. Proposes to transfer `amount` out of the joint account to the main purse of `target`.
+#[no_mangle]
+pub extern "C" fn propose_joint_collect() {
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let target: AccountHash = runtime::get_named_arg(TARGET);
+    propose(JointAction::Collect, target, amount, None);
+}
+
+// Owner only. Proposes to add `owner` to the owners of the joint account, any `threshold` of whom can act
+// on it from then on.
+#[no_mangle]
+pub extern "C" fn propose_add_owner() {
+    let owner: AccountHash = runtime::get_named_arg(OWNER);
+    let threshold: u32 = runtime::get_named_arg(THRESHOLD);
+    propose(JointAction::AddOwner, owner, U512::zero(), Some(threshold));
+}
+
+// Owner only. Proposes to remove `owner` from the owners of the joint account, any `threshold` of the
+// remaining owners can act on it from then on.
+#[no_mangle]
+pub extern "C" fn propose_remove_owner() {
+    let owner: AccountHash = runtime::get_named_arg(OWNER);
+    let threshold: u32 = runtime::get_named_arg(THRESHOLD);
+    propose(
+        JointAction::RemoveOwner,
+        owner,
+        U512::zero(),
+        Some(threshold),
+    );
+}
+
+// Owner only. Approves a proposal on a joint account.
+#[no_mangle]
+pub extern "C" fn approve_joint_proposal() {
+    let proposal_id: u64 = runtime::get_named_arg(PROPOSAL_ID);
+    let proposal = get_dictionary_value(JOINT_PROPOSALS, &proposal_id.to_string())
+        .unwrap_or_revert_with(Error::ProposalNotFound);
+    approve(proposal_id, proposal);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_JOINT_ACCOUNT,
+        vec![
+            Parameter::new(OWNERS, Vec::<AccountHash>::cl_type()),
+            Parameter::new(THRESHOLD, u32::cl_type()),
+        ],
+        AccountHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        PROPOSE_JOINT_COLLECT,
+        vec![
+            Parameter::new(JOINT_ACCOUNT, AccountHash::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(TARGET, AccountHash::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [PROPOSE_ADD_OWNER, PROPOSE_REMOVE_OWNER] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![
+                Parameter::new(JOINT_ACCOUNT, AccountHash::cl_type()),
+                Parameter::new(OWNER, AccountHash::cl_type()),
+                Parameter::new(THRESHOLD, u32::cl_type()),
+            ],
+            CLType::U64,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        APPROVE_JOINT_PROPOSAL,
+        vec![Parameter::new(PROPOSAL_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
@@ -0,0 +1,160 @@
+use alloc::{vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        DAILY_LIMIT, DAILY_LIMITS, DAILY_LIMIT_DELAY, DAILY_LIMIT_WINDOW, DEPOSIT_RECIPIENT,
+        GET_REMAINING_ALLOWANCE, SET_DAILY_LIMIT,
+    },
+    error::Error,
+    utils::{current_time, dictionary_key, get_dictionary_value, set_dictionary_value},
+};
+
+// A daily limit caps how much can leave the deposit of the recipient within any rolling 24 hours of block time:
+// their collects and finalized vault withdrawals, the payment flows they fund out of it, and what their
+// beneficiary or guardians move out of it. The contract keeps the withdrawals of the last 24 hours to tell
+// what is left of the allowance. Lowering the limit takes effect immediately, while raising or removing it
+// only takes effect after `DAILY_LIMIT_DELAY`, so a stolen key cannot lift the limit to drain the deposit.
+// For the same reason, changing the beneficiary or the guardians waits `DAILY_LIMIT_DELAY` while a limit is set.
+struct DailyLimit {
+    limit: Option<U512>,
+    // A higher limit waiting for its delay to pass, and the block time it takes effect at.
+    pending: Option<(Option<U512>, u64)>,
+    // The block time and amount of every collect within the window.
+    withdrawals: Vec<(u64, U512)>,
+}
+
+stored_struct!(DailyLimit {
+    limit,
+    pending,
+    withdrawals,
+});
+
+impl DailyLimit {
+    // The limit in effect, counting a pending change as soon as its delay has passed.
+    fn current_limit(&self) -> Option<U512> {
+        match self.pending {
+            Some((limit, effective_at)) if effective_at <= current_time() => limit,
+            _ => self.limit,
+        }
+    }
+
+    // Drops the collects that left the window.
+    fn prune(&mut self) {
+        let now = current_time();
+        self.withdrawals
+            .retain(|(collected_at, _)| collected_at + DAILY_LIMIT_WINDOW > now);
+    }
+
+    // What is left of the allowance in the current window, if there is a limit.
+    fn remaining(&self) -> Option<U512> {
+        self.current_limit().map(|limit| {
+            let collected = self
+                .withdrawals
+                .iter()
+                .fold(U512::zero(), |total, (_, amount)| total + *amount);
+            limit.saturating_sub(collected)
+        })
+    }
+}
+
+fn recipient_daily_limit(recipient: AccountHash) -> DailyLimit {
+    let mut daily_limit = get_dictionary_value(DAILY_LIMITS, &dictionary_key(&recipient))
+        .unwrap_or(DailyLimit {
+            limit: None,
+            pending: None,
+            withdrawals: Vec::new(),
+        });
+    daily_limit.prune();
+    daily_limit
+}
+
+fn write_daily_limit(recipient: AccountHash, daily_limit: DailyLimit) {
+    set_dictionary_value(DAILY_LIMITS, &dictionary_key(&recipient), daily_limit);
+}
+
+// Whether the recipient has a daily limit in effect.
+pub fn has_limit(recipient: AccountHash) -> bool {
+    recipient_daily_limit(recipient).current_limit().is_some()
+}
+
+// Books as much of `amount` leaving the deposit of the recipient as their allowance covers, and returns it.
+// Reverts with `Error::DailyLimitExceeded` if nothing of a non-zero amount is left.
+pub fn record_withdrawal_up_to(recipient: AccountHash, amount: U512) -> U512 {
+    let mut daily_limit = recipient_daily_limit(recipient);
+    let allowed = match daily_limit.remaining() {
+        None => return amount,
+        Some(remaining) => amount.min(remaining),
+    };
+    if allowed.is_zero() && !amount.is_zero() {
+        runtime::revert(Error::DailyLimitExceeded);
+    }
+    daily_limit.withdrawals.push((current_time(), allowed));
+    write_daily_limit(recipient, daily_limit);
+    allowed
+}
+
+// Books `amount` leaving the deposit of the recipient, reverting with `Error::DailyLimitExceeded`
+// if it takes them over their limit.
+pub fn record_withdrawal(recipient: AccountHash, amount: U512) {
+    let mut daily_limit = recipient_daily_limit(recipient);
+    match daily_limit.remaining() {
+        None => return,
+        Some(remaining) if amount > remaining => runtime::revert(Error::DailyLimitExceeded),
+        Some(_) => daily_limit.withdrawals.push((current_time(), amount)),
+    }
+    write_daily_limit(recipient, daily_limit);
+}
+
+// Sets or, when passed `None`, removes the daily limit of the caller.
+#[no_mangle]
+pub extern "C" fn set_daily_limit() {
+    let recipient = runtime::get_caller();
+    let limit: Option<U512> = runtime::get_named_arg(DAILY_LIMIT);
+    let mut daily_limit = recipient_daily_limit(recipient);
+    let current = daily_limit.current_limit();
+    let lowering = match (limit, current) {
+        (Some(limit), Some(current)) => limit <= current,
+        (Some(_), None) => true,
+        (None, _) => current.is_none(),
+    };
+    // Persist a change that already took effect before it gets replaced.
+    daily_limit.limit = current;
+    daily_limit.pending = None;
+    if lowering {
+        daily_limit.limit = limit;
+    } else {
+        daily_limit.pending = Some((limit, current_time() + DAILY_LIMIT_DELAY));
+    }
+    write_daily_limit(recipient, daily_limit);
+}
+
+// Returns what is left of the daily allowance of the recipient, or `None` if they have no limit.
+#[no_mangle]
+pub extern "C" fn get_remaining_allowance() {
+    let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let remaining = recipient_daily_limit(recipient).remaining();
+    runtime::ret(CLValue::from_t(remaining).unwrap_or_revert());
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_DAILY_LIMIT,
+        vec![Parameter::new(DAILY_LIMIT, Option::<U512>::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        GET_REMAINING_ALLOWANCE,
+        vec![Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type())],
+        Option::<U512>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
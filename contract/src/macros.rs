@@ -0,0 +1,58 @@
+// Implements `ToBytes`, `FromBytes` and `CLTyped` for a struct by serializing the listed fields in order,
+// so the state of the payment flows can be stored in contract dictionaries.
+macro_rules! stored_struct {
+    ($name:ident { $($field:ident),* $(,)? }) => {
+        impl casper_types::bytesrepr::ToBytes for $name {
+            fn to_bytes(&self) -> Result<alloc::vec::Vec<u8>, casper_types::bytesrepr::Error> {
+                let mut buffer = casper_types::bytesrepr::allocate_buffer(self)?;
+                $(buffer.extend(casper_types::bytesrepr::ToBytes::to_bytes(&self.$field)?);)*
+                Ok(buffer)
+            }
+
+            fn serialized_length(&self) -> usize {
+                [$(casper_types::bytesrepr::ToBytes::serialized_length(&self.$field)),*]
+                    .iter()
+                    .sum()
+            }
+        }
+
+        impl casper_types::bytesrepr::FromBytes for $name {
+            fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), casper_types::bytesrepr::Error> {
+                let remainder = bytes;
+                $(let ($field, remainder) = casper_types::bytesrepr::FromBytes::from_bytes(remainder)?;)*
+                Ok((Self { $($field),* }, remainder))
+            }
+        }
+
+        impl casper_types::CLTyped for $name {
+            fn cl_type() -> casper_types::CLType {
+                casper_types::CLType::Any
+            }
+        }
+    };
+}
+
+// Implements `ToBytes` and `FromBytes` for a fieldless enum by serializing the variant as a `u8`.
+macro_rules! stored_enum {
+    ($name:ident { $($variant:ident),* $(,)? }) => {
+        impl casper_types::bytesrepr::ToBytes for $name {
+            fn to_bytes(&self) -> Result<alloc::vec::Vec<u8>, casper_types::bytesrepr::Error> {
+                casper_types::bytesrepr::ToBytes::to_bytes(&(*self as u8))
+            }
+
+            fn serialized_length(&self) -> usize {
+                casper_types::bytesrepr::U8_SERIALIZED_LENGTH
+            }
+        }
+
+        impl casper_types::bytesrepr::FromBytes for $name {
+            fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), casper_types::bytesrepr::Error> {
+                let (value, remainder) = <u8 as casper_types::bytesrepr::FromBytes>::from_bytes(bytes)?;
+                $(if value == $name::$variant as u8 {
+                    return Ok(($name::$variant, remainder));
+                })*
+                Err(casper_types::bytesrepr::Error::Formatting)
+            }
+        }
+    };
+}
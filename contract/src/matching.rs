@@ -0,0 +1,191 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CHARITY, CHARITY_MATCHING_POOLS, CLOSE_MATCHING_POOL, FUND_MATCHING_POOL,
+        GET_MATCHING_POOL_REMAINING, GET_TOTAL_MATCHED, MATCHING_POOLS, MATCHING_POOL_COUNT,
+        MATCHING_POOL_ID, MATCHING_PURSE, MAX_MATCHING_POOLS, MAX_MATCHING_RATIO,
+        MIN_MATCHING_POOL, RATIO,
+    },
+    credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{dictionary_key, get_dictionary_value, named_purse, next_id, set_dictionary_value},
+};
+
+// Matching pools let a sponsor match the donations to a charity. The sponsor funds the pool out of their
+// deposit, which caps the total it can match, and sets the `ratio` in basis points, 10000 matching every
+// mote one to one. Every `deposit` to the charity is then matched from its pools, in the order they were
+// funded, with the matched amount credited to the deposit of the charity on top of the donation.
+// A donation too small to be matched with a single mote after rounding is skipped by the pool, which stays in
+// place until it runs out or the sponsor closes it. Deposits the charity makes to itself are not matched, nor are deposits
+// through `get_deposit_purse`, which bypass the contract. The contract cannot tell the accounts of a charity
+// apart, so a charity that donates to itself from another account is matched like any other donor.
+struct MatchingPool {
+    sponsor: AccountHash,
+    charity: AccountHash,
+    ratio: u32,
+    remaining: U512,
+    matched: U512,
+}
+
+stored_struct!(MatchingPool {
+    sponsor,
+    charity,
+    ratio,
+    remaining,
+    matched,
+});
+
+fn matching_pool_arg() -> (u64, MatchingPool) {
+    let pool_id: u64 = runtime::get_named_arg(MATCHING_POOL_ID);
+    let pool = get_dictionary_value(MATCHING_POOLS, &pool_id.to_string())
+        .unwrap_or_revert_with(Error::MatchingPoolNotFound);
+    (pool_id, pool)
+}
+
+fn write_matching_pool(pool_id: u64, pool: MatchingPool) {
+    set_dictionary_value(MATCHING_POOLS, &pool_id.to_string(), pool);
+}
+
+// The ids of the pools of the charity that have not run out or been closed.
+fn charity_pools(charity: AccountHash) -> Vec<u64> {
+    get_dictionary_value(CHARITY_MATCHING_POOLS, &dictionary_key(&charity)).unwrap_or_default()
+}
+
+fn set_charity_pools(charity: AccountHash, pool_ids: Vec<u64>) {
+    set_dictionary_value(CHARITY_MATCHING_POOLS, &dictionary_key(&charity), pool_ids);
+}
+
+// Called by `deposit` once `amount` reached the deposit of `recipient`. Matches the donation from every pool
+// of the recipient, as far as what is left in the pool goes, and drops the pools that ran out.
+pub fn match_deposit(recipient: AccountHash, amount: U512) {
+    let pool_ids = charity_pools(recipient);
+    if pool_ids.is_empty() || runtime::get_caller() == recipient {
+        return;
+    }
+    let purse = named_purse(MATCHING_PURSE);
+    let mut active_pool_ids = Vec::new();
+    for pool_id in pool_ids {
+        let mut pool: MatchingPool =
+            get_dictionary_value(MATCHING_POOLS, &pool_id.to_string()).unwrap_or_revert();
+        let matched = (amount * U512::from(pool.ratio) / U512::from(10_000)).min(pool.remaining);
+        if matched.is_zero() {
+            active_pool_ids.push(pool_id);
+            continue;
+        }
+        credit_deposit(purse, recipient, matched);
+        pool.remaining -= matched;
+        pool.matched += matched;
+        if !pool.remaining.is_zero() {
+            active_pool_ids.push(pool_id);
+        }
+        write_matching_pool(pool_id, pool);
+    }
+    set_charity_pools(recipient, active_pool_ids);
+}
+
+// Funds a pool matching the deposits to `charity` at `ratio` basis points with `amount` out of the callers
+// deposit, and returns its id. The ratio is capped at `MAX_MATCHING_RATIO`, and the pools of a charity at
+// `MAX_MATCHING_POOLS`, each funded with at least `MIN_MATCHING_POOL`.
+#[no_mangle]
+pub extern "C" fn fund_matching_pool() {
+    let sponsor = runtime::get_caller();
+    let charity: AccountHash = runtime::get_named_arg(CHARITY);
+    let ratio: u32 = runtime::get_named_arg(RATIO);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    if ratio == 0 || ratio > MAX_MATCHING_RATIO {
+        runtime::revert(Error::InvalidRatio);
+    }
+    if amount < U512::from(MIN_MATCHING_POOL) {
+        runtime::revert(Error::MatchingPoolTooSmall);
+    }
+    let mut pool_ids = charity_pools(charity);
+    if pool_ids.len() >= MAX_MATCHING_POOLS {
+        runtime::revert(Error::TooManyMatchingPools);
+    }
+    get_or_create_deposit_purse(charity);
+    spend_deposit(sponsor, named_purse(MATCHING_PURSE), amount);
+    let pool_id = next_id(MATCHING_POOL_COUNT);
+    write_matching_pool(
+        pool_id,
+        MatchingPool {
+            sponsor,
+            charity,
+            ratio,
+            remaining: amount,
+            matched: U512::zero(),
+        },
+    );
+    pool_ids.push(pool_id);
+    set_charity_pools(charity, pool_ids);
+    runtime::ret(CLValue::from_t(pool_id).unwrap_or_revert());
+}
+
+// Sponsor only. Stops the pool from matching, crediting what is left in it back to the deposit of the sponsor.
+#[no_mangle]
+pub extern "C" fn close_matching_pool() {
+    let (pool_id, mut pool) = matching_pool_arg();
+    if runtime::get_caller() != pool.sponsor {
+        runtime::revert(Error::NotSponsor);
+    }
+    if !pool.remaining.is_zero() {
+        credit_deposit(named_purse(MATCHING_PURSE), pool.sponsor, pool.remaining);
+    }
+    let mut pool_ids = charity_pools(pool.charity);
+    pool_ids.retain(|id| *id != pool_id);
+    set_charity_pools(pool.charity, pool_ids);
+    pool.remaining = U512::zero();
+    write_matching_pool(pool_id, pool);
+}
+
+// Returns what is left in the pool for matching.
+#[no_mangle]
+pub extern "C" fn get_matching_pool_remaining() {
+    let (_, pool) = matching_pool_arg();
+    runtime::ret(CLValue::from_t(pool.remaining).unwrap_or_revert());
+}
+
+// Returns the total the pool matched so far.
+#[no_mangle]
+pub extern "C" fn get_total_matched() {
+    let (_, pool) = matching_pool_arg();
+    runtime::ret(CLValue::from_t(pool.matched).unwrap_or_revert());
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        FUND_MATCHING_POOL,
+        vec![
+            Parameter::new(CHARITY, AccountHash::cl_type()),
+            Parameter::new(RATIO, u32::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CLOSE_MATCHING_POOL,
+        vec![Parameter::new(MATCHING_POOL_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [GET_MATCHING_POOL_REMAINING, GET_TOTAL_MATCHED] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![Parameter::new(MATCHING_POOL_ID, u64::cl_type())],
+            U512::cl_type(),
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+}
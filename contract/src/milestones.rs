@@ -0,0 +1,144 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        APPROVE_MILESTONE, CANCEL_MILESTONE, ESCROW_ID, ESCROW_PURSE, MILESTONE, MILESTONES,
+        MILESTONE_ESCROWS, MILESTONE_ESCROW_COUNT, OPEN_MILESTONE_ESCROW, PAYEE,
+    },
+    credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{get_dictionary_value, named_purse, next_id, set_dictionary_value},
+};
+
+// Milestone escrows split one payment into slices that the payer signs off one at a time.
+// The whole amount is taken out of the deposit of the payer up front and held in the escrow purse.
+// Pending -> Approved:  the slice is credited to the deposit of the payee.
+// Pending -> Cancelled: the slice is credited back to the deposit of the payer.
+// Both moves are up to the payer, use an arbiter escrow if the payee needs protection against cancellation.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MilestoneStatus {
+    Pending,
+    Approved,
+    Cancelled,
+}
+
+stored_enum!(MilestoneStatus {
+    Pending,
+    Approved,
+    Cancelled,
+});
+
+struct MilestoneEscrow {
+    payer: AccountHash,
+    payee: AccountHash,
+    amounts: Vec<U512>,
+    statuses: Vec<MilestoneStatus>,
+}
+
+stored_struct!(MilestoneEscrow {
+    payer,
+    payee,
+    amounts,
+    statuses,
+});
+
+// Opens a milestone escrow paying `milestones` (one amount per milestone) out of the callers deposit,
+// and returns its id. There has to be at least one milestone, and none of them can be for nothing.
+#[no_mangle]
+pub extern "C" fn open_milestone_escrow() {
+    let payer = runtime::get_caller();
+    let payee: AccountHash = runtime::get_named_arg(PAYEE);
+    let amounts: Vec<U512> = runtime::get_named_arg(MILESTONES);
+    if amounts.is_empty() || amounts.iter().any(|amount| amount.is_zero()) {
+        runtime::revert(Error::InvalidMilestones);
+    }
+    get_or_create_deposit_purse(payee);
+    let total = amounts
+        .iter()
+        .fold(U512::zero(), |total, amount| total + *amount);
+    spend_deposit(payer, named_purse(ESCROW_PURSE), total);
+    let escrow_id = next_id(MILESTONE_ESCROW_COUNT);
+    let statuses = vec![MilestoneStatus::Pending; amounts.len()];
+    set_dictionary_value(
+        MILESTONE_ESCROWS,
+        &escrow_id.to_string(),
+        MilestoneEscrow {
+            payer,
+            payee,
+            amounts,
+            statuses,
+        },
+    );
+    runtime::ret(CLValue::from_t(escrow_id).unwrap_or_revert());
+}
+
+// Payer only. Moves the pending `milestone` of the escrow to `status`,
+// and credits its amount to `payee` or back to the payer accordingly.
+fn close_milestone(status: MilestoneStatus) {
+    let escrow_id: u64 = runtime::get_named_arg(ESCROW_ID);
+    let milestone: u32 = runtime::get_named_arg(MILESTONE);
+    let mut escrow: MilestoneEscrow =
+        get_dictionary_value(MILESTONE_ESCROWS, &escrow_id.to_string())
+            .unwrap_or_revert_with(Error::EscrowNotFound);
+    if runtime::get_caller() != escrow.payer {
+        runtime::revert(Error::NotPayer);
+    }
+    let index = milestone as usize;
+    match escrow.statuses.get(index) {
+        Some(MilestoneStatus::Pending) => {}
+        Some(_) => runtime::revert(Error::MilestoneNotPending),
+        None => runtime::revert(Error::MilestoneNotFound),
+    }
+    let recipient = match status {
+        MilestoneStatus::Approved => escrow.payee,
+        _ => escrow.payer,
+    };
+    credit_deposit(named_purse(ESCROW_PURSE), recipient, escrow.amounts[index]);
+    escrow.statuses[index] = status;
+    set_dictionary_value(MILESTONE_ESCROWS, &escrow_id.to_string(), escrow);
+}
+
+// Payer only. Approves a pending milestone, paying its amount to the payee.
+#[no_mangle]
+pub extern "C" fn approve_milestone() {
+    close_milestone(MilestoneStatus::Approved);
+}
+
+// Payer only. Cancels a pending milestone, returning its amount to the payer.
+#[no_mangle]
+pub extern "C" fn cancel_milestone() {
+    close_milestone(MilestoneStatus::Cancelled);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        OPEN_MILESTONE_ESCROW,
+        vec![
+            Parameter::new(PAYEE, AccountHash::cl_type()),
+            Parameter::new(MILESTONES, Vec::<U512>::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [APPROVE_MILESTONE, CANCEL_MILESTONE] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![
+                Parameter::new(ESCROW_ID, u64::cl_type()),
+                Parameter::new(MILESTONE, u32::cl_type()),
+            ],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+}
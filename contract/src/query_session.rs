@@ -0,0 +1,86 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U512,
+};
+mod constants;
+use constants::{
+    DEPOSIT_CONTRACT_HASH, DEPOSIT_RECIPIENT, ENTRY_POINT, GET_DEPOSIT_BALANCE, GET_GOAL_BALANCE,
+    GET_MATCHING_POOL_REMAINING, GET_MERCHANT_SUBSCRIPTIONS, GET_REMAINING_ALLOWANCE,
+    GET_TOTAL_MATCHED, GOAL_NAME, IS_REGISTERED, MATCHING_POOL_ID, MERCHANT, QUERY_RESULT, TAG,
+};
+
+// Session code that executes in the callers context.
+// Calls the getter `entry_point` of the contract and stores what it returns under `query_result`
+// in the named keys of the account, where it can be read like any other stored value.
+// Each getter takes its own arguments, which are passed on to it.
+#[no_mangle]
+pub extern "C" fn call() {
+    let deposit_contract_hash: ContractHash = runtime::get_named_arg(DEPOSIT_CONTRACT_HASH);
+    let entry_point: String = runtime::get_named_arg(ENTRY_POINT);
+    let result = match entry_point.as_str() {
+        IS_REGISTERED => {
+            let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+            let registered: bool = runtime::call_contract(
+                deposit_contract_hash,
+                IS_REGISTERED,
+                runtime_args! {DEPOSIT_RECIPIENT => recipient},
+            );
+            storage::new_uref(registered)
+        }
+        GET_DEPOSIT_BALANCE => {
+            let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+            let tag: Option<u64> = runtime::get_named_arg(TAG);
+            let balance: U512 = runtime::call_contract(
+                deposit_contract_hash,
+                GET_DEPOSIT_BALANCE,
+                runtime_args! {DEPOSIT_RECIPIENT => recipient, TAG => tag},
+            );
+            storage::new_uref(balance)
+        }
+        GET_GOAL_BALANCE => {
+            let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+            let goal_name: String = runtime::get_named_arg(GOAL_NAME);
+            let balance: U512 = runtime::call_contract(
+                deposit_contract_hash,
+                GET_GOAL_BALANCE,
+                runtime_args! {DEPOSIT_RECIPIENT => recipient, GOAL_NAME => goal_name},
+            );
+            storage::new_uref(balance)
+        }
+        GET_MERCHANT_SUBSCRIPTIONS => {
+            let merchant: AccountHash = runtime::get_named_arg(MERCHANT);
+            let subscription_ids: Vec<u64> = runtime::call_contract(
+                deposit_contract_hash,
+                GET_MERCHANT_SUBSCRIPTIONS,
+                runtime_args! {MERCHANT => merchant},
+            );
+            storage::new_uref(subscription_ids)
+        }
+        GET_MATCHING_POOL_REMAINING | GET_TOTAL_MATCHED => {
+            let pool_id: u64 = runtime::get_named_arg(MATCHING_POOL_ID);
+            let amount: U512 = runtime::call_contract(
+                deposit_contract_hash,
+                entry_point.as_str(),
+                runtime_args! {MATCHING_POOL_ID => pool_id},
+            );
+            storage::new_uref(amount)
+        }
+        GET_REMAINING_ALLOWANCE => {
+            let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+            let remaining: Option<U512> = runtime::call_contract(
+                deposit_contract_hash,
+                GET_REMAINING_ALLOWANCE,
+                runtime_args! {DEPOSIT_RECIPIENT => recipient},
+            );
+            storage::new_uref(remaining)
+        }
+        _ => runtime::revert(ApiError::InvalidArgument),
+    };
+    runtime::put_key(QUERY_RESULT, result.into());
+}
@@ -0,0 +1,253 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        CIRCLES, CIRCLE_COUNT, CIRCLE_ID, CIRCLE_PURSE, CONTRIBUTION, CREATE_CIRCLE, EXIT_CIRCLE,
+        MARK_DEFAULTED, MEMBER, MEMBERS, PAYOUT_ROUND, PAY_ROUND, PENALTY, ROUND_LENGTH,
+    },
+    credit_deposit,
+    error::Error,
+    spend_deposit,
+    utils::{
+        current_time, get_dictionary_value, has_duplicates, named_purse, next_id,
+        set_dictionary_value,
+    },
+};
+
+// Rotating savings circles: every member pays `contribution` out of their deposit each round, and each
+// round the whole pot is credited to the deposit of one member, in the order of `members`, from where
+// they collect it. A round is due at the end of its `round_length` (in milliseconds of block time,
+// counted from the creation of the circle), and paying after that costs an extra `penalty`, which goes
+// into the pot. Members who have not received the pot yet can exit: they stop paying and lose their turn.
+// Their contribution to the current round is repaid to them out of the pot, while what they paid into
+// earlier rounds stays with the members who received those pots. Once a round is overdue, anyone can mark
+// a member who has not paid for it as defaulted, which removes them from the circle like an exit, so the
+// round can be paid out without them.
+struct Circle {
+    members: Vec<AccountHash>,
+    paid_rounds: Vec<u64>,
+    received: Vec<bool>,
+    exited: Vec<bool>,
+    contribution: U512,
+    penalty: U512,
+    round_length: u64,
+    started_at: u64,
+    round: u64,
+    pot: U512,
+}
+
+stored_struct!(Circle {
+    members,
+    paid_rounds,
+    received,
+    exited,
+    contribution,
+    penalty,
+    round_length,
+    started_at,
+    round,
+    pot,
+});
+
+impl Circle {
+    // The index of `account` among the members, reverting if they are not one of them or exited.
+    fn member_index(&self, account: AccountHash) -> usize {
+        let index = self
+            .members
+            .iter()
+            .position(|member| *member == account)
+            .unwrap_or_revert_with(Error::NotMember);
+        if self.exited[index] {
+            runtime::revert(Error::MemberExited);
+        }
+        index
+    }
+
+    fn caller_index(&self) -> usize {
+        self.member_index(runtime::get_caller())
+    }
+
+    fn has_paid_round(&self, index: usize) -> bool {
+        self.paid_rounds[index] > self.round
+    }
+
+    // The block time from which the current round is overdue, `u64::MAX` if it would come later than that.
+    fn due_at(&self) -> u64 {
+        self.started_at
+            .saturating_add((self.round + 1).saturating_mul(self.round_length))
+    }
+
+    // The index of the member that receives the pot of the current round, reverting if everyone has had their turn.
+    fn recipient_index(&self) -> usize {
+        (0..self.members.len())
+            .find(|index| !self.received[*index] && !self.exited[*index])
+            .unwrap_or_revert_with(Error::CircleFinished)
+    }
+}
+
+fn circle_arg() -> (u64, Circle) {
+    let circle_id: u64 = runtime::get_named_arg(CIRCLE_ID);
+    let circle = get_dictionary_value(CIRCLES, &circle_id.to_string())
+        .unwrap_or_revert_with(Error::CircleNotFound);
+    (circle_id, circle)
+}
+
+fn write_circle(circle_id: u64, circle: Circle) {
+    set_dictionary_value(CIRCLES, &circle_id.to_string(), circle);
+}
+
+// Creates a savings circle starting with the current round, and returns its id.
+// The deposit purses of the members are only created once something is credited to them, e.g. their pot.
+#[no_mangle]
+pub extern "C" fn create_circle() {
+    let members: Vec<AccountHash> = runtime::get_named_arg(MEMBERS);
+    let contribution: U512 = runtime::get_named_arg(CONTRIBUTION);
+    let penalty: U512 = runtime::get_named_arg(PENALTY);
+    let round_length: u64 = runtime::get_named_arg(ROUND_LENGTH);
+    if members.is_empty() || has_duplicates(&members) || contribution.is_zero() || round_length == 0
+    {
+        runtime::revert(Error::InvalidCircle);
+    }
+    let circle_id = next_id(CIRCLE_COUNT);
+    write_circle(
+        circle_id,
+        Circle {
+            paid_rounds: vec![0; members.len()],
+            received: vec![false; members.len()],
+            exited: vec![false; members.len()],
+            members,
+            contribution,
+            penalty,
+            round_length,
+            started_at: current_time(),
+            round: 0,
+            pot: U512::zero(),
+        },
+    );
+    runtime::ret(CLValue::from_t(circle_id).unwrap_or_revert());
+}
+
+// Member only. Pays the contribution of the caller for the current round, plus the penalty if the round is overdue.
+#[no_mangle]
+pub extern "C" fn pay_round() {
+    let (circle_id, mut circle) = circle_arg();
+    let index = circle.caller_index();
+    circle.recipient_index();
+    if circle.has_paid_round(index) {
+        runtime::revert(Error::RoundAlreadyPaid);
+    }
+    let amount = if current_time() >= circle.due_at() {
+        circle.contribution + circle.penalty
+    } else {
+        circle.contribution
+    };
+    spend_deposit(circle.members[index], named_purse(CIRCLE_PURSE), amount);
+    circle.paid_rounds[index] += 1;
+    circle.pot += amount;
+    write_circle(circle_id, circle);
+}
+
+// Pays out the pot of the current round to the member whose turn it is, once every member that has not
+// exited paid for it.
+#[no_mangle]
+pub extern "C" fn payout_round() {
+    let (circle_id, mut circle) = circle_arg();
+    let recipient = circle.recipient_index();
+    let fully_paid =
+        (0..circle.members.len()).all(|index| circle.exited[index] || circle.has_paid_round(index));
+    if !fully_paid {
+        runtime::revert(Error::RoundNotFullyPaid);
+    }
+    if !circle.pot.is_zero() {
+        credit_deposit(
+            named_purse(CIRCLE_PURSE),
+            circle.members[recipient],
+            circle.pot,
+        );
+    }
+    circle.pot = U512::zero();
+    circle.received[recipient] = true;
+    circle.round += 1;
+    write_circle(circle_id, circle);
+}
+
+// Member only. Leaves the circle before receiving the pot. If the caller paid for the current round,
+// their contribution is repaid out of the pot, the penalty for paying late stays in it.
+#[no_mangle]
+pub extern "C" fn exit_circle() {
+    let (circle_id, mut circle) = circle_arg();
+    let index = circle.caller_index();
+    if circle.received[index] {
+        runtime::revert(Error::PotAlreadyReceived);
+    }
+    if circle.has_paid_round(index) && !circle.contribution.is_zero() {
+        credit_deposit(
+            named_purse(CIRCLE_PURSE),
+            circle.members[index],
+            circle.contribution,
+        );
+        circle.pot -= circle.contribution;
+    }
+    circle.exited[index] = true;
+    write_circle(circle_id, circle);
+}
+
+// Marks `member` as defaulted once the current round is overdue and they have not paid for it, so the round
+// can be paid out without them. A defaulted member is out of the circle, and loses their turn if they
+// have not received the pot yet.
+#[no_mangle]
+pub extern "C" fn mark_defaulted() {
+    let (circle_id, mut circle) = circle_arg();
+    let member: AccountHash = runtime::get_named_arg(MEMBER);
+    let index = circle.member_index(member);
+    circle.recipient_index();
+    if circle.has_paid_round(index) {
+        runtime::revert(Error::RoundAlreadyPaid);
+    }
+    if current_time() < circle.due_at() {
+        runtime::revert(Error::RoundNotOverdue);
+    }
+    circle.exited[index] = true;
+    write_circle(circle_id, circle);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_CIRCLE,
+        vec![
+            Parameter::new(MEMBERS, Vec::<AccountHash>::cl_type()),
+            Parameter::new(CONTRIBUTION, U512::cl_type()),
+            Parameter::new(PENALTY, U512::cl_type()),
+            Parameter::new(ROUND_LENGTH, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [PAY_ROUND, PAYOUT_ROUND, EXIT_CIRCLE] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![Parameter::new(CIRCLE_ID, u64::cl_type())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        MARK_DEFAULTED,
+        vec![
+            Parameter::new(CIRCLE_ID, u64::cl_type()),
+            Parameter::new(MEMBER, AccountHash::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
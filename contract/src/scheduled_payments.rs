@@ -0,0 +1,196 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Key, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CANCEL_PAYMENT, DEPOSIT_RECIPIENT, EXECUTE_AFTER, EXECUTE_DUE, KEEPER_TIP,
+        PAYMENT_ID, PAYMENT_IDS, SCHEDULED_PAYMENTS, SCHEDULED_PAYMENT_COUNT,
+        SCHEDULED_PAYMENT_PURSE, SCHEDULE_PAYMENT, SET_KEEPER_TIP,
+    },
+    create_deposit_purse, credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{
+        current_time, get_dictionary_value, named_purse, next_id, read_named_value, require_owner,
+        set_dictionary_value, write_named_value,
+    },
+};
+
+// Scheduled payments settle on a date without the payer being online. The payer locks the amount,
+// plus the keeper tip configured by the owner at that time, out of their deposit. Once `execute_after`
+// has passed, any account can execute the payment, crediting the amount to the deposit of the recipient
+// and the tip to its own deposit. Until then, the payer can cancel it and get both back.
+// Executing payments counts as opting in for deposits, so keepers get their tips even while
+// registration is required, and a payment cannot get stuck for want of a registered keeper.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PaymentStatus {
+    Scheduled,
+    Executed,
+    Cancelled,
+}
+
+stored_enum!(PaymentStatus {
+    Scheduled,
+    Executed,
+    Cancelled,
+});
+
+struct ScheduledPayment {
+    payer: AccountHash,
+    recipient: AccountHash,
+    amount: U512,
+    tip: U512,
+    execute_after: u64,
+    status: PaymentStatus,
+}
+
+stored_struct!(ScheduledPayment {
+    payer,
+    recipient,
+    amount,
+    tip,
+    execute_after,
+    status,
+});
+
+fn read_payment(payment_id: u64) -> Option<ScheduledPayment> {
+    get_dictionary_value(SCHEDULED_PAYMENTS, &payment_id.to_string())
+}
+
+fn write_payment(payment_id: u64, payment: ScheduledPayment) {
+    set_dictionary_value(SCHEDULED_PAYMENTS, &payment_id.to_string(), payment);
+}
+
+// Schedules a payment of `amount` to `recipient` out of the callers deposit, and returns its id.
+#[no_mangle]
+pub extern "C" fn schedule_payment() {
+    let payer = runtime::get_caller();
+    let recipient: Key = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let execute_after: u64 = runtime::get_named_arg(EXECUTE_AFTER);
+    let recipient = recipient.into_account().unwrap_or_revert();
+    let tip: U512 = read_named_value(KEEPER_TIP);
+    get_or_create_deposit_purse(recipient);
+    spend_deposit(payer, named_purse(SCHEDULED_PAYMENT_PURSE), amount + tip);
+    let payment_id = next_id(SCHEDULED_PAYMENT_COUNT);
+    write_payment(
+        payment_id,
+        ScheduledPayment {
+            payer,
+            recipient,
+            amount,
+            tip,
+            execute_after,
+            status: PaymentStatus::Scheduled,
+        },
+    );
+    runtime::ret(CLValue::from_t(payment_id).unwrap_or_revert());
+}
+
+// Executes the payments in `payment_ids` that are due, and pays their tips to the caller.
+// Payments that are unknown, not due yet or no longer scheduled are skipped, so one stale id
+// does not fail the whole batch. Reverts with `Error::NoPaymentDue` if none of them could be executed.
+#[no_mangle]
+pub extern "C" fn execute_due() {
+    let payment_ids: Vec<u64> = runtime::get_named_arg(PAYMENT_IDS);
+    let purse = named_purse(SCHEDULED_PAYMENT_PURSE);
+    let now = current_time();
+    let mut executed = false;
+    let mut tips = U512::zero();
+    for payment_id in payment_ids {
+        let mut payment = match read_payment(payment_id) {
+            Some(payment)
+                if payment.status == PaymentStatus::Scheduled && payment.execute_after <= now =>
+            {
+                payment
+            }
+            _ => continue,
+        };
+        credit_deposit(purse, payment.recipient, payment.amount);
+        tips += payment.tip;
+        executed = true;
+        payment.status = PaymentStatus::Executed;
+        write_payment(payment_id, payment);
+    }
+    if !executed {
+        runtime::revert(Error::NoPaymentDue);
+    }
+    if !tips.is_zero() {
+        let keeper = runtime::get_caller();
+        if !runtime::has_key(&keeper.to_string()) {
+            create_deposit_purse(keeper);
+        }
+        credit_deposit(purse, keeper, tips);
+    }
+}
+
+// Payer only. Cancels a scheduled payment, crediting its amount and tip back to the deposit of the payer.
+#[no_mangle]
+pub extern "C" fn cancel_payment() {
+    let payment_id: u64 = runtime::get_named_arg(PAYMENT_ID);
+    let mut payment = read_payment(payment_id).unwrap_or_revert_with(Error::PaymentNotFound);
+    if runtime::get_caller() != payment.payer {
+        runtime::revert(Error::NotPayer);
+    }
+    if payment.status != PaymentStatus::Scheduled {
+        runtime::revert(Error::PaymentNotScheduled);
+    }
+    credit_deposit(
+        named_purse(SCHEDULED_PAYMENT_PURSE),
+        payment.payer,
+        payment.amount + payment.tip,
+    );
+    payment.status = PaymentStatus::Cancelled;
+    write_payment(payment_id, payment);
+}
+
+// Owner only. Sets the tip that is locked with every newly scheduled payment and paid to whoever executes it.
+#[no_mangle]
+pub extern "C" fn set_keeper_tip() {
+    require_owner();
+    let keeper_tip: U512 = runtime::get_named_arg(KEEPER_TIP);
+    write_named_value(KEEPER_TIP, keeper_tip);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SCHEDULE_PAYMENT,
+        vec![
+            Parameter::new(DEPOSIT_RECIPIENT, Key::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(EXECUTE_AFTER, u64::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        EXECUTE_DUE,
+        vec![Parameter::new(PAYMENT_IDS, Vec::<u64>::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CANCEL_PAYMENT,
+        vec![Parameter::new(PAYMENT_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_KEEPER_TIP,
+        vec![Parameter::new(KEEPER_TIP, U512::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
@@ -0,0 +1,217 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CANCEL_SUBSCRIPTION, CHARGE, FUNDING, GET_MERCHANT_SUBSCRIPTIONS, MERCHANT,
+        MERCHANT_SUBSCRIPTIONS, PERIOD, SUBSCRIBE, SUBSCRIPTIONS, SUBSCRIPTION_COUNT,
+        SUBSCRIPTION_ID, SUBSCRIPTION_PURSE,
+    },
+    credit_deposit,
+    error::Error,
+    get_or_create_deposit_purse, spend_deposit,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, named_purse, next_id,
+        set_dictionary_value,
+    },
+};
+
+// Subscriptions let a merchant pull a fixed `amount` once per `period` (in milliseconds of block time)
+// from a balance the subscriber funded out of their deposit up front. Periods are counted from the
+// block time of `subscribe`, and a period the merchant did not charge in is skipped, not carried over.
+// The subscriber can cancel at any time, which credits the unused balance back to their deposit.
+// A subscription also ends once the charge that leaves less than `amount` in its balance went through,
+// so the merchant only ever lists subscriptions it can still charge.
+struct Subscription {
+    subscriber: AccountHash,
+    merchant: AccountHash,
+    amount: U512,
+    period: u64,
+    started_at: u64,
+    next_charge_at: u64,
+    balance: U512,
+    active: bool,
+}
+
+stored_struct!(Subscription {
+    subscriber,
+    merchant,
+    amount,
+    period,
+    started_at,
+    next_charge_at,
+    balance,
+    active,
+});
+
+// Reads the subscription passed in as `subscription_id`, reverting if it has ended.
+fn subscription_arg() -> (u64, Subscription) {
+    let subscription_id: u64 = runtime::get_named_arg(SUBSCRIPTION_ID);
+    let subscription: Subscription =
+        get_dictionary_value(SUBSCRIPTIONS, &subscription_id.to_string())
+            .unwrap_or_revert_with(Error::SubscriptionNotFound);
+    if !subscription.active {
+        runtime::revert(Error::SubscriptionCancelled);
+    }
+    (subscription_id, subscription)
+}
+
+fn write_subscription(subscription_id: u64, subscription: Subscription) {
+    set_dictionary_value(SUBSCRIPTIONS, &subscription_id.to_string(), subscription);
+}
+
+// The ids of the subscriptions of the merchant that have not ended.
+fn merchant_subscriptions(merchant: AccountHash) -> Vec<u64> {
+    get_dictionary_value(MERCHANT_SUBSCRIPTIONS, &dictionary_key(&merchant)).unwrap_or_default()
+}
+
+fn set_merchant_subscriptions(merchant: AccountHash, subscription_ids: Vec<u64>) {
+    set_dictionary_value(
+        MERCHANT_SUBSCRIPTIONS,
+        &dictionary_key(&merchant),
+        subscription_ids,
+    );
+}
+
+// Subscribes the caller to `merchant`, moving `funding` out of their deposit into the subscription,
+// and returns the id of the subscription. The first period starts right away, so it can be charged immediately.
+#[no_mangle]
+pub extern "C" fn subscribe() {
+    let subscriber = runtime::get_caller();
+    let merchant: AccountHash = runtime::get_named_arg(MERCHANT);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let period: u64 = runtime::get_named_arg(PERIOD);
+    let funding: U512 = runtime::get_named_arg(FUNDING);
+    if period == 0 {
+        runtime::revert(Error::InvalidPeriod);
+    }
+    // A subscription charging nothing would never run out of funding, and so never end.
+    if amount.is_zero() {
+        runtime::revert(Error::InvalidSubscriptionAmount);
+    }
+    if funding < amount {
+        runtime::revert(Error::InsufficientBalance);
+    }
+    get_or_create_deposit_purse(merchant);
+    spend_deposit(subscriber, named_purse(SUBSCRIPTION_PURSE), funding);
+    let subscription_id = next_id(SUBSCRIPTION_COUNT);
+    let now = current_time();
+    write_subscription(
+        subscription_id,
+        Subscription {
+            subscriber,
+            merchant,
+            amount,
+            period,
+            started_at: now,
+            next_charge_at: now,
+            balance: funding,
+            active: true,
+        },
+    );
+    let mut subscription_ids = merchant_subscriptions(merchant);
+    subscription_ids.push(subscription_id);
+    set_merchant_subscriptions(merchant, subscription_ids);
+    runtime::ret(CLValue::from_t(subscription_id).unwrap_or_revert());
+}
+
+// Takes the subscription off the list of the merchant and credits the unused balance back to the
+// deposit of the subscriber.
+fn end_subscription(subscription_id: u64, mut subscription: Subscription) {
+    let mut subscription_ids = merchant_subscriptions(subscription.merchant);
+    subscription_ids.retain(|id| *id != subscription_id);
+    set_merchant_subscriptions(subscription.merchant, subscription_ids);
+    if !subscription.balance.is_zero() {
+        credit_deposit(
+            named_purse(SUBSCRIPTION_PURSE),
+            subscription.subscriber,
+            subscription.balance,
+        );
+    }
+    subscription.balance = U512::zero();
+    subscription.active = false;
+    write_subscription(subscription_id, subscription);
+}
+
+// Merchant only. Charges the amount of the subscription for the current period into the deposit of the merchant,
+// ending the subscription if what is left cannot fund another period.
+#[no_mangle]
+pub extern "C" fn charge() {
+    let (subscription_id, mut subscription) = subscription_arg();
+    if runtime::get_caller() != subscription.merchant {
+        runtime::revert(Error::NotMerchant);
+    }
+    let now = current_time();
+    if now < subscription.next_charge_at {
+        runtime::revert(Error::ChargeNotDue);
+    }
+    credit_deposit(
+        named_purse(SUBSCRIPTION_PURSE),
+        subscription.merchant,
+        subscription.amount,
+    );
+    let periods_passed = (now - subscription.started_at) / subscription.period;
+    subscription.next_charge_at = subscription
+        .started_at
+        .saturating_add((periods_passed + 1).saturating_mul(subscription.period));
+    subscription.balance -= subscription.amount;
+    if subscription.balance < subscription.amount {
+        end_subscription(subscription_id, subscription);
+    } else {
+        write_subscription(subscription_id, subscription);
+    }
+}
+
+// Subscriber only. Ends the subscription and credits the unused balance back to the deposit of the subscriber.
+#[no_mangle]
+pub extern "C" fn cancel_subscription() {
+    let (subscription_id, subscription) = subscription_arg();
+    if runtime::get_caller() != subscription.subscriber {
+        runtime::revert(Error::NotSubscriber);
+    }
+    end_subscription(subscription_id, subscription);
+}
+
+// Returns the ids of the active subscriptions of the merchant.
+#[no_mangle]
+pub extern "C" fn get_merchant_subscriptions() {
+    let merchant: AccountHash = runtime::get_named_arg(MERCHANT);
+    runtime::ret(CLValue::from_t(merchant_subscriptions(merchant)).unwrap_or_revert());
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SUBSCRIBE,
+        vec![
+            Parameter::new(MERCHANT, AccountHash::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(PERIOD, u64::cl_type()),
+            Parameter::new(FUNDING, U512::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    for entry_point in [CHARGE, CANCEL_SUBSCRIPTION] {
+        entry_points.add_entry_point(EntryPoint::new(
+            entry_point,
+            vec![Parameter::new(SUBSCRIPTION_ID, u64::cl_type())],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+    }
+
+    entry_points.add_entry_point(EntryPoint::new(
+        GET_MERCHANT_SUBSCRIPTIONS,
+        vec![Parameter::new(MERCHANT, AccountHash::cl_type())],
+        Vec::<u64>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
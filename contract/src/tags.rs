@@ -0,0 +1,98 @@
+use casper_contract::{
+    contract_api::{runtime, system::get_purse_balance},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{account::AccountHash, URef, U512};
+
+use crate::{
+    constants::{TAGGED_TOTALS, TAG_BALANCES, TAG_GENERATIONS},
+    error::Error,
+    utils::{dictionary_key, get_dictionary_value, set_dictionary_value},
+};
+
+// Destination tags split the deposit purse of a recipient into sub-ledgers, so for example an exchange
+// can receive the deposits of all its customers to a single account and still tell them apart.
+// The motes of every tag stay in the one deposit purse of the recipient. The contract keeps the balance
+// of each (recipient, tag) pair and the sum of them per recipient, but no list of the tags in use, so the
+// cost of a deposit or collect does not grow with the number of tags anyone deposited under.
+// Whatever the purse holds beyond the tagged sum is untagged, e.g. deposits made through `get_deposit_purse`,
+// and only that part can be spent without naming a tag.
+// Tag balances are stored per generation of the recipient, so moving the whole deposit elsewhere can forget
+// all of its tags at once by starting a new generation.
+
+fn generation(recipient: AccountHash) -> u64 {
+    get_dictionary_value(TAG_GENERATIONS, &dictionary_key(&recipient)).unwrap_or_default()
+}
+
+fn tag_balance(recipient: AccountHash, tag: u64) -> U512 {
+    get_dictionary_value(
+        TAG_BALANCES,
+        &dictionary_key(&(recipient, generation(recipient), tag)),
+    )
+    .unwrap_or_default()
+}
+
+fn tagged_total(recipient: AccountHash) -> U512 {
+    get_dictionary_value(TAGGED_TOTALS, &dictionary_key(&recipient)).unwrap_or_default()
+}
+
+fn set_tag_balance(recipient: AccountHash, tag: u64, balance: U512) {
+    set_dictionary_value(
+        TAG_BALANCES,
+        &dictionary_key(&(recipient, generation(recipient), tag)),
+        balance,
+    );
+}
+
+fn set_tagged_total(recipient: AccountHash, total: U512) {
+    set_dictionary_value(TAGGED_TOTALS, &dictionary_key(&recipient), total);
+}
+
+// The balance of the recipient under `tag`, or the whole deposit purse if no tag is given.
+pub fn balance(recipient: AccountHash, deposit_purse: URef, tag: Option<u64>) -> U512 {
+    match tag {
+        Some(tag) => tag_balance(recipient, tag),
+        None => get_purse_balance(deposit_purse).unwrap_or_revert(),
+    }
+}
+
+// What the recipient can spend under `tag`, or the untagged part of the deposit if no tag is given.
+pub fn available(recipient: AccountHash, deposit_purse: URef, tag: Option<u64>) -> U512 {
+    match tag {
+        Some(tag) => tag_balance(recipient, tag),
+        None => balance(recipient, deposit_purse, None).saturating_sub(tagged_total(recipient)),
+    }
+}
+
+// Books motes that were just transferred into the deposit purse of the recipient under `tag`.
+pub fn credit(recipient: AccountHash, tag: u64, amount: U512) {
+    set_tag_balance(recipient, tag, tag_balance(recipient, tag) + amount);
+    set_tagged_total(recipient, tagged_total(recipient) + amount);
+}
+
+// Books motes that are about to leave the deposit purse of the recipient, reverting with
+// `Error::InsufficientBalance` if the tag, or the untagged part of the deposit if no tag is given,
+// holds less than `amount`.
+pub fn debit(recipient: AccountHash, deposit_purse: URef, amount: U512, tag: Option<u64>) {
+    if amount > available(recipient, deposit_purse, tag) {
+        runtime::revert(Error::InsufficientBalance);
+    }
+    if let Some(tag) = tag {
+        set_tag_balance(recipient, tag, tag_balance(recipient, tag) - amount);
+        set_tagged_total(recipient, tagged_total(recipient) - amount);
+    }
+}
+
+// Forgets every tag of the recipient, so the whole deposit counts as untagged. Used before the deposit
+// is moved to someone else as a whole, e.g. by a dead man's switch or a recovery.
+pub fn clear(recipient: AccountHash) {
+    if tagged_total(recipient).is_zero() {
+        return;
+    }
+    set_dictionary_value(
+        TAG_GENERATIONS,
+        &dictionary_key(&recipient),
+        generation(recipient) + 1,
+    );
+    set_tagged_total(recipient, U512::zero());
+}
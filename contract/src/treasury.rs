@@ -0,0 +1,266 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use casper_contract::{
+    contract_api::{runtime, system::transfer_from_purse_to_purse},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, bytesrepr::ToBytes, CLType, CLTyped, CLValue, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CREATE_SPENDING_PROPOSAL, CREATE_TREASURY, DEPOSIT_RECIPIENT,
+        EXECUTE_SPENDING_PROPOSAL, MEMBERS, MEMO, PROPOSAL_ID, QUORUM, SPENDING_PROPOSALS,
+        SPENDING_PROPOSAL_COUNT, SUPPORT, TREASURIES, TREASURY, TREASURY_COUNT, VOTE,
+        VOTING_PERIOD,
+    },
+    deposit_purse,
+    error::Error,
+    get_or_create_deposit_purse, tags,
+    utils::{
+        current_time, dictionary_key, get_dictionary_value, has_duplicates, next_id,
+        set_dictionary_value,
+    },
+};
+
+// Treasuries are deposit slots governed by weighted voting. Like joint accounts, each one gets an account
+// hash of its own, derived from the blake2b hash of its id, which is deposited to like any other recipient.
+// Members propose spending an amount to a recipient, and vote with their weight until `voting_period`
+// milliseconds of block time after the proposal was made. Once voting ended, anyone can execute a proposal
+// that passed: at least `quorum` weight voted on it, and more of it for the proposal than against it.
+// Executing it moves the amount out of the deposit of the treasury into the deposit of the recipient.
+struct Treasury {
+    members: Vec<(AccountHash, u64)>,
+    quorum: u64,
+    voting_period: u64,
+}
+
+stored_struct!(Treasury {
+    members,
+    quorum,
+    voting_period,
+});
+
+impl Treasury {
+    // The voting weight of the caller, reverting if they are not a member.
+    fn weight_of_caller(&self) -> u64 {
+        let caller = runtime::get_caller();
+        self.members
+            .iter()
+            .find(|(member, _)| *member == caller)
+            .map(|(_, weight)| *weight)
+            .unwrap_or_revert_with(Error::NotTreasuryMember)
+    }
+}
+
+struct SpendingProposal {
+    treasury: AccountHash,
+    recipient: AccountHash,
+    amount: U512,
+    memo: String,
+    voting_ends_at: u64,
+    weight_for: u64,
+    weight_against: u64,
+    voters: Vec<AccountHash>,
+    executed: bool,
+}
+
+stored_struct!(SpendingProposal {
+    treasury,
+    recipient,
+    amount,
+    memo,
+    voting_ends_at,
+    weight_for,
+    weight_against,
+    voters,
+    executed,
+});
+
+fn treasury(treasury: AccountHash) -> Treasury {
+    get_dictionary_value(TREASURIES, &dictionary_key(&treasury))
+        .unwrap_or_revert_with(Error::TreasuryNotFound)
+}
+
+fn proposal_arg() -> (u64, SpendingProposal) {
+    let proposal_id: u64 = runtime::get_named_arg(PROPOSAL_ID);
+    let proposal = get_dictionary_value(SPENDING_PROPOSALS, &proposal_id.to_string())
+        .unwrap_or_revert_with(Error::ProposalNotFound);
+    (proposal_id, proposal)
+}
+
+fn write_proposal(proposal_id: u64, proposal: SpendingProposal) {
+    set_dictionary_value(SPENDING_PROPOSALS, &proposal_id.to_string(), proposal);
+}
+
+// Creates a treasury of `members` with their voting weights, and returns its account hash.
+// Every member is listed once, and the total weight has to fit into a u64, so the votes on a proposal
+// cannot add up past it.
+#[no_mangle]
+pub extern "C" fn create_treasury() {
+    let members: Vec<(AccountHash, u64)> = runtime::get_named_arg(MEMBERS);
+    let quorum: u64 = runtime::get_named_arg(QUORUM);
+    let voting_period: u64 = runtime::get_named_arg(VOTING_PERIOD);
+    let accounts: Vec<AccountHash> = members.iter().map(|(member, _)| *member).collect();
+    if has_duplicates(&accounts) {
+        runtime::revert(Error::InvalidMembers);
+    }
+    let total_weight = members
+        .iter()
+        .try_fold(0u64, |total, (_, weight)| total.checked_add(*weight))
+        .unwrap_or_revert_with(Error::InvalidMembers);
+    if quorum == 0 || quorum > total_weight {
+        runtime::revert(Error::InvalidQuorum);
+    }
+    let id = next_id(TREASURY_COUNT);
+    let treasury_hash = AccountHash::new(runtime::blake2b(
+        (TREASURIES.to_string(), id).to_bytes().unwrap_or_revert(),
+    ));
+    get_or_create_deposit_purse(treasury_hash);
+    set_dictionary_value(
+        TREASURIES,
+        &dictionary_key(&treasury_hash),
+        Treasury {
+            members,
+            quorum,
+            voting_period,
+        },
+    );
+    runtime::ret(CLValue::from_t(treasury_hash).unwrap_or_revert());
+}
+
+// Member only. Proposes spending `amount` out of the treasury to `recipient`, and returns the id of the proposal.
+#[no_mangle]
+pub extern "C" fn create_spending_proposal() {
+    let treasury_hash: AccountHash = runtime::get_named_arg(TREASURY);
+    let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let memo: String = runtime::get_named_arg(MEMO);
+    let treasury = treasury(treasury_hash);
+    treasury.weight_of_caller();
+    get_or_create_deposit_purse(recipient);
+    let proposal_id = next_id(SPENDING_PROPOSAL_COUNT);
+    write_proposal(
+        proposal_id,
+        SpendingProposal {
+            treasury: treasury_hash,
+            recipient,
+            amount,
+            memo,
+            voting_ends_at: current_time().saturating_add(treasury.voting_period),
+            weight_for: 0,
+            weight_against: 0,
+            voters: Vec::new(),
+            executed: false,
+        },
+    );
+    runtime::ret(CLValue::from_t(proposal_id).unwrap_or_revert());
+}
+
+// Member only. Votes for or, when `support` is false, against the proposal with the weight of the caller.
+#[no_mangle]
+pub extern "C" fn vote() {
+    let (proposal_id, mut proposal) = proposal_arg();
+    let support: bool = runtime::get_named_arg(SUPPORT);
+    let weight = treasury(proposal.treasury).weight_of_caller();
+    if current_time() >= proposal.voting_ends_at {
+        runtime::revert(Error::VotingClosed);
+    }
+    let voter = runtime::get_caller();
+    if proposal.voters.contains(&voter) {
+        runtime::revert(Error::AlreadyVoted);
+    }
+    proposal.voters.push(voter);
+    let tally = if support {
+        &mut proposal.weight_for
+    } else {
+        &mut proposal.weight_against
+    };
+    *tally = tally.checked_add(weight).unwrap_or_revert();
+    write_proposal(proposal_id, proposal);
+}
+
+// Moves the amount of a proposal that passed out of the treasury into the deposit of the recipient,
+// once voting ended.
+#[no_mangle]
+pub extern "C" fn execute_spending_proposal() {
+    let (proposal_id, mut proposal) = proposal_arg();
+    if proposal.executed {
+        runtime::revert(Error::ProposalExecuted);
+    }
+    if current_time() < proposal.voting_ends_at {
+        runtime::revert(Error::VotingNotEnded);
+    }
+    let quorum = treasury(proposal.treasury).quorum;
+    let turnout = proposal
+        .weight_for
+        .checked_add(proposal.weight_against)
+        .unwrap_or_revert();
+    if turnout < quorum || proposal.weight_for <= proposal.weight_against {
+        runtime::revert(Error::ProposalRejected);
+    }
+    // A treasury cannot name a tag, so whatever was deposited to it under one counts as untagged.
+    tags::clear(proposal.treasury);
+    let treasury_purse = deposit_purse(proposal.treasury);
+    tags::debit(proposal.treasury, treasury_purse, proposal.amount, None);
+    transfer_from_purse_to_purse(
+        treasury_purse,
+        get_or_create_deposit_purse(proposal.recipient),
+        proposal.amount,
+        None,
+    )
+    .unwrap_or_revert();
+    proposal.executed = true;
+    write_proposal(proposal_id, proposal);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_TREASURY,
+        vec![
+            Parameter::new(MEMBERS, Vec::<(AccountHash, u64)>::cl_type()),
+            Parameter::new(QUORUM, u64::cl_type()),
+            Parameter::new(VOTING_PERIOD, u64::cl_type()),
+        ],
+        AccountHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CREATE_SPENDING_PROPOSAL,
+        vec![
+            Parameter::new(TREASURY, AccountHash::cl_type()),
+            Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type()),
+            Parameter::new(AMOUNT, U512::cl_type()),
+            Parameter::new(MEMO, String::cl_type()),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        VOTE,
+        vec![
+            Parameter::new(PROPOSAL_ID, u64::cl_type()),
+            Parameter::new(SUPPORT, bool::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        EXECUTE_SPENDING_PROPOSAL,
+        vec![Parameter::new(PROPOSAL_ID, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
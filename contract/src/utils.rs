@@ -0,0 +1,105 @@
+use alloc::{format, string::String};
+use casper_contract::{
+    contract_api::{runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{FromBytes, ToBytes},
+    CLTyped, Key, URef,
+};
+
+use crate::{constants::OWNER, error::Error};
+
+fn named_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .and_then(Key::into_uref)
+        .unwrap_or_revert()
+}
+
+// Reads a value stored behind a URef in the named keys of the contract.
+pub fn read_named_value<T: CLTyped + FromBytes>(name: &str) -> T {
+    storage::read(named_uref(name))
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+// Overwrites a value stored behind a URef in the named keys of the contract.
+pub fn write_named_value<T: CLTyped + ToBytes>(name: &str, value: T) {
+    storage::write(named_uref(name), value);
+}
+
+// Dictionary item keys are limited to 64 bytes, so items are stored under the hex encoded
+// blake2b hash of whatever identifies them, e.g. an account hash or an (account hash, tag) pair.
+pub fn dictionary_key<T: ToBytes>(value: &T) -> String {
+    runtime::blake2b(value.to_bytes().unwrap_or_revert())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Reads an item from the dictionary stored under `name` in the contract.
+// Dictionaries are created on first write, so a missing dictionary reads as a missing item.
+pub fn get_dictionary_value<T: CLTyped + FromBytes>(name: &str, key: &str) -> Option<T> {
+    let dictionary = runtime::get_key(name)?.into_uref().unwrap_or_revert();
+    storage::dictionary_get(dictionary, key).unwrap_or_revert()
+}
+
+// Writes an item into the dictionary stored under `name` in the contract, creating the dictionary if needed.
+pub fn set_dictionary_value<T: CLTyped + ToBytes>(name: &str, key: &str, value: T) {
+    let dictionary = match runtime::get_key(name) {
+        Some(key) => key.into_uref().unwrap_or_revert(),
+        None => storage::new_dictionary(name).unwrap_or_revert(),
+    };
+    storage::dictionary_put(dictionary, key, value);
+}
+
+// Returns the next id of the counter stored under `name` in the contract, starting at 0.
+pub fn next_id(name: &str) -> u64 {
+    match runtime::get_key(name) {
+        Some(key) => {
+            let counter = key.into_uref().unwrap_or_revert();
+            let id: u64 = storage::read(counter).unwrap_or_revert().unwrap_or_revert();
+            storage::write(counter, id + 1);
+            id
+        }
+        None => {
+            runtime::put_key(name, storage::new_uref(1u64).into());
+            0
+        }
+    }
+}
+
+// Returns the purse stored under `name` in the contract, creating it on first use.
+// The payment flows each hold the motes they lock up in such a purse, and keep their books in dictionaries.
+pub fn named_purse(name: &str) -> URef {
+    match runtime::get_key(name) {
+        Some(key) => key.into_uref().unwrap_or_revert(),
+        None => {
+            let new_purse = system::create_purse();
+            runtime::put_key(name, new_purse.into());
+            new_purse
+        }
+    }
+}
+
+// Whether any item appears more than once, e.g. an account listed twice as a participant.
+pub fn has_duplicates<T: PartialEq>(items: &[T]) -> bool {
+    items
+        .iter()
+        .enumerate()
+        .any(|(index, item)| items[..index].contains(item))
+}
+
+// Current block time in milliseconds.
+pub fn current_time() -> u64 {
+    runtime::get_blocktime().into()
+}
+
+// Reverts with `Error::NotOwner` unless the caller is the account that installed the contract.
+pub fn require_owner() {
+    let owner: AccountHash = read_named_value(OWNER);
+    if runtime::get_caller() != owner {
+        runtime::revert(Error::NotOwner);
+    }
+}
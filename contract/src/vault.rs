@@ -0,0 +1,189 @@
+use alloc::vec;
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U512,
+};
+
+use crate::{
+    constants::{
+        AMOUNT, CANCEL_WITHDRAWAL, DEPOSIT_RECIPIENT, FINALIZE_WITHDRAWAL, REQUEST_WITHDRAWAL,
+        SET_VAULT_DELAY, VAULTS, WITHDRAWAL_DELAY,
+    },
+    dead_man_switch, deposit_purse,
+    error::Error,
+    guardians::is_guardian,
+    limits, tags,
+    utils::{current_time, dictionary_key, get_dictionary_value, set_dictionary_value},
+    withdrawal_address::withdrawal_address,
+};
+
+// Vault mode turns collecting into two phases for deposits like treasuries: the recipient requests a
+// withdrawal, and can only finalize it once `withdrawal_delay` milliseconds of block time have passed.
+// Until then the recipient, or any of the guardians they registered, can cancel it, so a stolen key
+// cannot drain the deposit before anyone notices. While the vault is on, `collect`, `collect_to_purse`
+// and the payment flows funded out of the deposit revert with `Error::VaultLocked`.
+// Raising the delay takes effect immediately, lowering it or turning the vault off (a delay of 0)
+// only once the current delay has passed. A delay that would end after `u64::MAX` never does,
+// so a vault with a delay of `u64::MAX` stays locked for good.
+struct Vault {
+    withdrawal_delay: u64,
+    // A lower delay waiting to take effect, and the block time it does.
+    pending_delay: Option<(u64, u64)>,
+    // The amount of the requested withdrawal, and the block time it can be finalized at.
+    withdrawal: Option<(U512, u64)>,
+}
+
+stored_struct!(Vault {
+    withdrawal_delay,
+    pending_delay,
+    withdrawal,
+});
+
+fn recipient_vault(recipient: AccountHash) -> Vault {
+    get_dictionary_value(VAULTS, &dictionary_key(&recipient)).unwrap_or(Vault {
+        withdrawal_delay: 0,
+        pending_delay: None,
+        withdrawal: None,
+    })
+}
+
+fn set_vault(recipient: AccountHash, vault: Vault) {
+    set_dictionary_value(VAULTS, &dictionary_key(&recipient), vault);
+}
+
+impl Vault {
+    // The delay in effect, counting a pending change as soon as it took effect.
+    fn current_delay(&self) -> u64 {
+        match self.pending_delay {
+            Some((delay, effective_at)) if effective_at <= current_time() => delay,
+            _ => self.withdrawal_delay,
+        }
+    }
+}
+
+// The withdrawal delay of the recipient in effect, 0 if their deposit is not in vault mode.
+pub fn current_delay(recipient: AccountHash) -> u64 {
+    recipient_vault(recipient).current_delay()
+}
+
+// Reverts with `Error::VaultLocked` if the deposit of the recipient is in vault mode.
+pub fn require_unlocked(recipient: AccountHash) {
+    if current_delay(recipient) > 0 {
+        runtime::revert(Error::VaultLocked);
+    }
+}
+
+// Sets the delay between requesting and finalizing withdrawals from the callers deposit, 0 turning the vault off.
+#[no_mangle]
+pub extern "C" fn set_vault_delay() {
+    let recipient = runtime::get_caller();
+    let withdrawal_delay: u64 = runtime::get_named_arg(WITHDRAWAL_DELAY);
+    let mut vault = recipient_vault(recipient);
+    let current_delay = vault.current_delay();
+    if withdrawal_delay >= current_delay {
+        vault.withdrawal_delay = withdrawal_delay;
+        vault.pending_delay = None;
+    } else {
+        vault.withdrawal_delay = current_delay;
+        vault.pending_delay = Some((
+            withdrawal_delay,
+            current_time().saturating_add(current_delay),
+        ));
+    }
+    set_vault(recipient, vault);
+}
+
+// Requests a withdrawal of `amount` from the callers deposit, which can be finalized after the delay.
+#[no_mangle]
+pub extern "C" fn request_withdrawal() {
+    let recipient = runtime::get_caller();
+    let amount: U512 = runtime::get_named_arg(AMOUNT);
+    let mut vault = recipient_vault(recipient);
+    let delay = vault.current_delay();
+    if delay == 0 {
+        runtime::revert(Error::VaultNotEnabled);
+    }
+    if vault.withdrawal.is_some() {
+        runtime::revert(Error::WithdrawalPending);
+    }
+    vault.withdrawal = Some((amount, current_time().saturating_add(delay)));
+    set_vault(recipient, vault);
+}
+
+// Transfers the requested withdrawal to the caller, or to their withdrawal address if they configured one,
+// once the delay has passed. Like a collect, this counts against the daily limit of the caller and as
+// activity for their dead man's switch.
+#[no_mangle]
+pub extern "C" fn finalize_withdrawal() {
+    let recipient = runtime::get_caller();
+    let mut vault = recipient_vault(recipient);
+    let (amount, executable_at) = vault
+        .withdrawal
+        .unwrap_or_revert_with(Error::NoWithdrawalPending);
+    if current_time() < executable_at {
+        runtime::revert(Error::WithdrawalNotReady);
+    }
+    let deposit_purse = deposit_purse(recipient);
+    tags::debit(recipient, deposit_purse, amount, None);
+    limits::record_withdrawal(recipient, amount);
+    dead_man_switch::record_activity(recipient);
+    let destination = withdrawal_address(recipient).unwrap_or(recipient);
+    system::transfer_from_purse_to_account(deposit_purse, destination, amount, None)
+        .unwrap_or_revert();
+    vault.withdrawal = None;
+    set_vault(recipient, vault);
+}
+
+// Recipient or guardian only. Cancels the requested withdrawal from the deposit of `recipient`.
+#[no_mangle]
+pub extern "C" fn cancel_withdrawal() {
+    let recipient: AccountHash = runtime::get_named_arg(DEPOSIT_RECIPIENT);
+    let caller = runtime::get_caller();
+    if caller != recipient && !is_guardian(recipient, caller) {
+        runtime::revert(Error::NotGuardian);
+    }
+    let mut vault = recipient_vault(recipient);
+    if vault.withdrawal.is_none() {
+        runtime::revert(Error::NoWithdrawalPending);
+    }
+    vault.withdrawal = None;
+    set_vault(recipient, vault);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_VAULT_DELAY,
+        vec![Parameter::new(WITHDRAWAL_DELAY, u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        REQUEST_WITHDRAWAL,
+        vec![Parameter::new(AMOUNT, U512::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        FINALIZE_WITHDRAWAL,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        CANCEL_WITHDRAWAL,
+        vec![Parameter::new(DEPOSIT_RECIPIENT, AccountHash::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
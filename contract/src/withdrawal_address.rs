@@ -0,0 +1,65 @@
+use alloc::vec;
+use casper_contract::contract_api::runtime;
+use casper_types::{
+    account::AccountHash, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter,
+};
+
+use crate::{
+    constants::{
+        PENDING_WITHDRAWAL_ADDRESSES, SET_WITHDRAWAL_ADDRESS, WITHDRAWAL_ADDRESS,
+        WITHDRAWAL_ADDRESSES, WITHDRAWAL_ADDRESS_DELAY,
+    },
+    utils::{current_time, dictionary_key, get_dictionary_value, set_dictionary_value},
+};
+
+// A change of the withdrawal address waiting for its delay to pass, and the block time it takes effect at.
+type PendingChange = Option<(Option<AccountHash>, u64)>;
+
+// The address the deposit of the recipient has to be collected to, if they configured one.
+// A pending change counts as soon as its delay has passed, even before it is written back.
+pub fn withdrawal_address(recipient: AccountHash) -> Option<AccountHash> {
+    let key = dictionary_key(&recipient);
+    let current = get_dictionary_value::<Option<AccountHash>>(WITHDRAWAL_ADDRESSES, &key).flatten();
+    match get_dictionary_value::<PendingChange>(PENDING_WITHDRAWAL_ADDRESSES, &key).flatten() {
+        Some((pending, effective_at)) if effective_at <= current_time() => pending,
+        _ => current,
+    }
+}
+
+// Sets or, when passed `None`, removes the withdrawal address of the caller.
+// Setting the first address takes effect immediately. Any later change, including removal,
+// only takes effect after `WITHDRAWAL_ADDRESS_DELAY`, so a stolen signing key cannot redirect
+// the deposit instantly, and the owner has time to move the funds with their current address.
+#[no_mangle]
+pub extern "C" fn set_withdrawal_address() {
+    let recipient = runtime::get_caller();
+    let address: Option<AccountHash> = runtime::get_named_arg(WITHDRAWAL_ADDRESS);
+    let key = dictionary_key(&recipient);
+    let current = withdrawal_address(recipient);
+    let pending: PendingChange = match current {
+        None => {
+            set_dictionary_value(WITHDRAWAL_ADDRESSES, &key, address);
+            None
+        }
+        Some(_) => {
+            // Persist a change that already took effect before it gets replaced.
+            set_dictionary_value(WITHDRAWAL_ADDRESSES, &key, current);
+            Some((address, current_time() + WITHDRAWAL_ADDRESS_DELAY))
+        }
+    };
+    set_dictionary_value(PENDING_WITHDRAWAL_ADDRESSES, &key, pending);
+}
+
+pub fn add_entry_points(entry_points: &mut EntryPoints) {
+    entry_points.add_entry_point(EntryPoint::new(
+        SET_WITHDRAWAL_ADDRESS,
+        vec![Parameter::new(
+            WITHDRAWAL_ADDRESS,
+            Option::<AccountHash>::cl_type(),
+        )],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+}
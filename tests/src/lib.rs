@@ -1,13 +1,27 @@
-use std::path::PathBuf;
+use std::{convert::TryInto, path::PathBuf};
 
 use casper_engine_test_support::{InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST};
 
 use casper_types::{account::AccountHash, runtime_args, PublicKey, RuntimeArgs, SecretKey, U512};
-use casper_types::{ContractHash, Key};
-use utils::{deploy, fund_account, query, DeploySource};
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    crypto, CLTyped, ContractHash, Key,
+};
+use utils::{
+    deploy, dictionary_key, expect_error, fund_account, last_transfers, query,
+    query_dictionary_item, DeploySource,
+};
 
+// The contract's error codes, shared with the tests so reverts can be asserted on by variant.
+#[allow(dead_code)]
+#[path = "../../contract/src/error.rs"]
+mod error;
+mod merkle;
 mod utils;
 
+use error::Error;
+use merkle::MerkleTree;
+
 pub struct PaymentContract {
     pub builder: InMemoryWasmTestBuilder,
     pub contract_hash: ContractHash,
@@ -17,7 +31,13 @@ pub struct PaymentContract {
 }
 
 impl PaymentContract {
+    /// Installs the contract without a minimum deposit and with registration turned off.
     pub fn deploy() -> Self {
+        Self::deploy_with_config(U512::zero(), false)
+    }
+
+    /// Installs the contract with the given `minimum_deposit` and `registration_required` settings.
+    pub fn deploy_with_config(minimum_deposit: U512, registration_required: bool) -> Self {
         // We create 3 accounts. "alice" will be the one who installs the contract.
         let alice_public_key: PublicKey =
             PublicKey::from(&SecretKey::ed25519_from_bytes([1u8; 32]).unwrap());
@@ -52,7 +72,10 @@ impl PaymentContract {
             &mut builder,
             &alice_account,
             &DeploySource::Code(code),
-            runtime_args! {},
+            runtime_args! {
+                "minimum_deposit" => minimum_deposit,
+                "registration_required" => registration_required
+            },
             true,
             None,
         );
@@ -92,6 +115,28 @@ impl PaymentContract {
 
     /// Function that handles the creation and execution of deploys.
     fn call(&mut self, caller: AccountHash, entry_point: &str, args: RuntimeArgs) {
+        self.call_with_result(caller, entry_point, args, true, None);
+    }
+
+    /// Same as `call`, but executes the deploy in a block with the given block time.
+    pub fn call_at(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        block_time: u64,
+    ) {
+        self.call_with_result(caller, entry_point, args, true, Some(block_time));
+    }
+
+    fn call_with_result(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        success: bool,
+        block_time: Option<u64>,
+    ) {
         deploy(
             &mut self.builder,
             &caller,
@@ -100,20 +145,127 @@ impl PaymentContract {
                 entry_point: entry_point.to_string(),
             },
             args,
-            true,
+            success,
+            block_time,
+        );
+    }
+
+    /// Calls an entry_point of the contract and asserts that it reverts with `error`.
+    pub fn call_expect_error(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        error: Error,
+    ) {
+        self.call_expect_error_at(caller, entry_point, args, error, None);
+    }
+
+    /// Same as `call_expect_error`, but executes the deploy in a block with the given block time.
+    pub fn call_expect_error_at(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        error: Error,
+        block_time: Option<u64>,
+    ) {
+        self.call_with_result(caller, entry_point, args, false, block_time);
+        expect_error(&self.builder, error.into());
+    }
+
+    /// Deploys a session wasm and asserts that it reverts with `error`.
+    pub fn session_expect_error(
+        &mut self,
+        sender: AccountHash,
+        wasm: &str,
+        args: RuntimeArgs,
+        error: Error,
+    ) {
+        deploy(
+            &mut self.builder,
+            &sender,
+            &DeploySource::Code(PathBuf::from(wasm)),
+            args,
+            false,
             None,
         );
+        expect_error(&self.builder, error.into());
+    }
+
+    /// Deploys the "query_session", which calls the getter `entry_point` of the contract with `args`
+    /// and stores what it returns in the named keys of the caller, and reads the result from there.
+    pub fn query_entry_point<T: CLTyped + FromBytes>(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+    ) -> T {
+        self.query_entry_point_at(caller, entry_point, args, None)
+    }
+
+    /// Same as `query_entry_point`, at the given block time if any.
+    pub fn query_entry_point_at<T: CLTyped + FromBytes>(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        mut args: RuntimeArgs,
+        block_time: Option<u64>,
+    ) -> T {
+        args.insert("deposit_contract_hash", self.contract_hash)
+            .unwrap();
+        args.insert("entry_point", entry_point.to_string()).unwrap();
+        deploy(
+            &mut self.builder,
+            &caller,
+            &DeploySource::Code(PathBuf::from("query_session.wasm")),
+            args,
+            true,
+            block_time,
+        );
+        query(
+            &self.builder,
+            Key::Account(caller),
+            &["query_result".to_string()],
+        )
+    }
+
+    /// Calls the `is_registered` entry_point for the account through the "query_session" deployed by `caller`.
+    pub fn is_registered(&mut self, caller: AccountHash, account: AccountHash) -> bool {
+        self.query_entry_point(
+            caller,
+            "is_registered",
+            runtime_args! {"recipient" => Key::Account(account)},
+        )
     }
 
     /// Deploys the "deposit_session" with recipient and the hash of the "deposit_contract",
     /// that creates a purse and transfers `amount` number of motes into it,
     /// then transfers said purse to the deposit entry_point of the contract.
-    pub fn deposit(&mut self, sender: AccountHash, recipient: Key, amount: U512) {
+    pub fn deposit(&mut self, sender: AccountHash, recipient: Key, amount: U512, id: Option<u64>) {
+        self.deposit_with(sender, recipient, amount, id, None);
+    }
+
+    /// Same as `deposit`, but books the deposit under a destination `tag` of the recipient.
+    pub fn deposit_tagged(&mut self, sender: AccountHash, recipient: Key, amount: U512, tag: u64) {
+        self.deposit_with(sender, recipient, amount, None, Some(tag));
+    }
+
+    fn deposit_with(
+        &mut self,
+        sender: AccountHash,
+        recipient: Key,
+        amount: U512,
+        id: Option<u64>,
+        tag: Option<u64>,
+    ) {
         let code = PathBuf::from("deposit_session.wasm");
         let args = runtime_args! {
             "deposit_contract_hash" => self.contract_hash,
             "recipient" => recipient,
-            "amount" => amount
+            "amount" => amount,
+            "id" => id,
+            "tag" => tag
         };
         deploy(
             &mut self.builder,
@@ -128,12 +280,19 @@ impl PaymentContract {
     /// Deploy "deposit_into_session" that has the same arguments as "deposit_session", but instead of
     /// passing in a purse to the contract to do a transfer of motes, this session asks the contract
     /// for a purse and deposits motes into it.
-    pub fn deposit_into(&mut self, sender: AccountHash, recipient: Key, amount: U512) {
+    pub fn deposit_into(
+        &mut self,
+        sender: AccountHash,
+        recipient: Key,
+        amount: U512,
+        id: Option<u64>,
+    ) {
         let code = PathBuf::from("deposit_into_session.wasm");
         let args = runtime_args! {
             "deposit_contract_hash" => self.contract_hash,
             "recipient" => recipient,
-            "amount" => amount
+            "amount" => amount,
+            "id" => id
         };
         deploy(
             &mut self.builder,
@@ -148,12 +307,209 @@ impl PaymentContract {
     /// Function that calls the `collect` endpoint on the deposit contract,
     /// that directly transfers the amount in the purse stored to the accounts hash to the account.
     pub fn collect(&mut self, recipient: AccountHash) {
-        self.call(
+        self.collect_with(recipient, None, None, None, None, None);
+    }
+
+    /// Calls `collect` with an optional `amount`, `target` account, transfer `id`
+    /// and destination `tag`, at the given block time if any.
+    pub fn collect_with(
+        &mut self,
+        recipient: AccountHash,
+        amount: Option<U512>,
+        target: Option<AccountHash>,
+        id: Option<u64>,
+        tag: Option<u64>,
+        block_time: Option<u64>,
+    ) {
+        self.call_with_result(
             recipient,
             "collect",
-            runtime_args! {"amount" => Option::<U512>::None},
+            runtime_args! {
+                "amount" => amount,
+                "target" => target,
+                "id" => id,
+                "tag" => tag
+            },
+            true,
+            block_time,
+        );
+    }
+
+    /// Deploys the "collect_session" that collects `amount` of the deposit of `recipient`
+    /// into the purse stored under `purse_name` in the account, creating it if necessary.
+    pub fn collect_to_purse(
+        &mut self,
+        recipient: AccountHash,
+        purse_name: &str,
+        amount: Option<U512>,
+        id: Option<u64>,
+    ) {
+        let code = PathBuf::from("collect_session.wasm");
+        let args = runtime_args! {
+            "deposit_contract_hash" => self.contract_hash,
+            "purse_name" => purse_name.to_string(),
+            "amount" => amount,
+            "id" => id,
+            "tag" => Option::<u64>::None
+        };
+        deploy(
+            &mut self.builder,
+            &recipient,
+            &DeploySource::Code(code),
+            args,
+            true,
+            None,
+        );
+    }
+
+    /// Getter function for the balance of a purse stored in the named keys of an account.
+    pub fn get_named_purse_balance(&self, account_key: &AccountHash, purse_name: &str) -> U512 {
+        let account = self
+            .builder
+            .get_account(*account_key)
+            .expect("should get account");
+        let purse = account
+            .named_keys()
+            .get(purse_name)
+            .and_then(Key::as_uref)
+            .expect("should have purse");
+        self.builder.get_purse_balance(*purse)
+    }
+
+    /// Getter function for the balance of the deposit purse the contract holds for an account.
+    pub fn get_deposit_balance(&self, account: AccountHash) -> U512 {
+        self.get_contract_purse_balance(&account.to_string())
+    }
+
+    /// Getter function for the balance of a purse stored in the named keys of the contract,
+    /// which is zero if the purse has not been created yet.
+    pub fn get_contract_purse_balance(&self, purse_name: &str) -> U512 {
+        self.builder
+            .get_contract(self.contract_hash)
+            .expect("should have contract")
+            .named_keys()
+            .get(purse_name)
+            .and_then(Key::as_uref)
+            .map(|purse| self.builder.get_purse_balance(*purse))
+            .unwrap_or_default()
+    }
+
+    /// Calls `open_escrow` to lock `amount` out of the deposit of `payer` for `payee`.
+    pub fn open_escrow(
+        &mut self,
+        payer: AccountHash,
+        payee: AccountHash,
+        arbiter: AccountHash,
+        amount: U512,
+    ) {
+        self.call(
+            payer,
+            "open_escrow",
+            runtime_args! {
+                "payee" => payee,
+                "arbiter" => arbiter,
+                "amount" => amount
+            },
+        );
+    }
+
+    /// Calls one of the escrow entry_points that only take the `escrow_id`.
+    pub fn escrow_call(&mut self, caller: AccountHash, entry_point: &str, escrow_id: u64) {
+        self.call(
+            caller,
+            entry_point,
+            runtime_args! {"escrow_id" => escrow_id},
+        );
+    }
+
+    /// Calls `approve_milestone` or `cancel_milestone` for a milestone of a milestone escrow.
+    pub fn milestone_call(
+        &mut self,
+        caller: AccountHash,
+        entry_point: &str,
+        escrow_id: u64,
+        milestone: u32,
+    ) {
+        self.call(
+            caller,
+            entry_point,
+            runtime_args! {"escrow_id" => escrow_id, "milestone" => milestone},
+        );
+    }
+
+    /// Reads an item from a dictionary of the contract, `None` if there is no such item.
+    pub fn get_dictionary_value<T: CLTyped + FromBytes>(
+        &self,
+        dictionary_name: &str,
+        key: String,
+    ) -> Option<T> {
+        query_dictionary_item(
+            &self.builder,
+            Key::from(self.contract_hash),
+            Some(dictionary_name.to_string()),
+            key,
+        )
+        .ok()
+        .map(|value| {
+            value
+                .as_cl_value()
+                .expect("should be cl value")
+                .clone()
+                .into_t()
+                .expect("wrong type in dictionary")
+        })
+    }
+
+    /// Signs a cheque for `redeem_cheque` with the secret key of the payer, and returns the raw signature.
+    pub fn sign_cheque(
+        &self,
+        secret_key: &SecretKey,
+        recipient: AccountHash,
+        amount: U512,
+        nonce: u64,
+        expiry: u64,
+    ) -> [u8; 64] {
+        let message = (self.contract_hash, recipient, amount, nonce, expiry)
+            .to_bytes()
+            .unwrap();
+        let signature = crypto::sign(message, secret_key, &PublicKey::from(secret_key));
+        // Drop the leading tag byte that marks an ed25519 signature.
+        signature.to_bytes().unwrap()[1..].try_into().unwrap()
+    }
+
+    /// Deploys the "contribute_session", which contributes `amount` from the main purse of the sender to a campaign.
+    pub fn contribute(&mut self, sender: AccountHash, campaign_id: u64, amount: U512) {
+        let code = PathBuf::from("contribute_session.wasm");
+        let args = runtime_args! {
+            "deposit_contract_hash" => self.contract_hash,
+            "campaign_id" => campaign_id,
+            "amount" => amount
+        };
+        deploy(
+            &mut self.builder,
+            &sender,
+            &DeploySource::Code(code),
+            args,
+            true,
+            None,
         );
     }
+
+    /// Funds one more account, derived from the secret key filled with `seed`, beyond alice, bob and charlie.
+    pub fn fund_new_account(&mut self, seed: u8) -> AccountHash {
+        let public_key = PublicKey::from(&SecretKey::ed25519_from_bytes([seed; 32]).unwrap());
+        let account = AccountHash::from(&public_key);
+        self.builder
+            .exec(fund_account(&account))
+            .expect_success()
+            .commit();
+        account
+    }
+
+    /// Calls `register_recipient` to opt the account in for receiving deposits.
+    pub fn register_recipient(&mut self, recipient: AccountHash) {
+        self.call(recipient, "register_recipient", runtime_args! {});
+    }
 }
 
 #[test]
@@ -172,6 +528,7 @@ fn test_payment_and_collect() {
         context.alice_account,
         Key::Account(context.charlie_account),
         U512::from(10000000000000u64),
+        None,
     );
 
     // look at balances again, alice money should be down by the deposited amount of 10000000000000,
@@ -206,6 +563,7 @@ fn test_multiple_payment_and_single_collect() {
         context.alice_account,
         Key::Account(context.charlie_account),
         U512::from(10000000000000u64),
+        None,
     );
 
     // bob also makes a deposit for charlie
@@ -213,6 +571,7 @@ fn test_multiple_payment_and_single_collect() {
         context.bob_account,
         Key::Account(context.charlie_account),
         U512::from(10000000000000u64),
+        None,
     );
 
     let account_balances = context.get_all_accounts_balance();
@@ -245,12 +604,14 @@ fn test_multiple_payment_and_single_collect_deposit_into() {
         context.alice_account,
         Key::Account(context.charlie_account),
         U512::from(10000000000000u64),
+        None,
     );
 
     context.deposit_into(
         context.bob_account,
         Key::Account(context.charlie_account),
         U512::from(10000000000000u64),
+        None,
     );
 
     let account_balances = context.get_all_accounts_balance();
@@ -266,3 +627,3140 @@ fn test_multiple_payment_and_single_collect_deposit_into() {
     assert_eq!(account_balances.1, U512::from(38500000000000_u64));
     assert_eq!(account_balances.2, U512::from(68500000000000_u64));
 }
+
+#[test]
+fn test_deposit_below_minimum_reverts() {
+    let mut context = PaymentContract::deploy_with_config(U512::from(5000000000000u64), false);
+
+    // a deposit below the minimum is rejected and creates no purse for charlie
+    context.session_expect_error(
+        context.alice_account,
+        "deposit_session.wasm",
+        runtime_args! {
+            "deposit_contract_hash" => context.contract_hash,
+            "recipient" => Key::Account(context.charlie_account),
+            "amount" => U512::from(1000000000000u64),
+            "id" => Option::<u64>::None,
+            "tag" => Option::<u64>::None
+        },
+        Error::BelowMinimumDeposit,
+    );
+    assert!(!context.is_registered(context.bob_account, context.charlie_account));
+
+    // a deposit of at least the minimum goes through
+    context.deposit(
+        context.alice_account,
+        Key::Account(context.charlie_account),
+        U512::from(5000000000000u64),
+        None,
+    );
+    assert!(context.is_registered(context.bob_account, context.charlie_account));
+
+    // only the owner can change the minimum
+    context.call_expect_error(
+        context.bob_account,
+        "set_minimum_deposit",
+        runtime_args! {"minimum_deposit" => U512::zero()},
+        Error::NotOwner,
+    );
+    context.call(
+        context.alice_account,
+        "set_minimum_deposit",
+        runtime_args! {"minimum_deposit" => U512::zero()},
+    );
+    context.deposit(
+        context.bob_account,
+        Key::Account(context.charlie_account),
+        U512::from(1000000000000u64),
+        None,
+    );
+
+    context.collect(context.charlie_account);
+    let account_balances = context.get_all_accounts_balance();
+    assert_eq!(account_balances.2, U512::from(54500000000000_u64));
+}
+
+#[test]
+fn test_registration_required() {
+    let mut context = PaymentContract::deploy_with_config(U512::zero(), true);
+
+    // neither deposit flow can create a purse for a recipient that has not opted in
+    context.session_expect_error(
+        context.alice_account,
+        "deposit_into_session.wasm",
+        runtime_args! {
+            "deposit_contract_hash" => context.contract_hash,
+            "recipient" => Key::Account(context.charlie_account),
+            "amount" => U512::from(10000000000000u64),
+            "id" => Option::<u64>::None
+        },
+        Error::RecipientNotRegistered,
+    );
+    context.session_expect_error(
+        context.alice_account,
+        "deposit_session.wasm",
+        runtime_args! {
+            "deposit_contract_hash" => context.contract_hash,
+            "recipient" => Key::Account(context.charlie_account),
+            "amount" => U512::from(10000000000000u64),
+            "id" => Option::<u64>::None,
+            "tag" => Option::<u64>::None
+        },
+        Error::RecipientNotRegistered,
+    );
+    assert!(!context.is_registered(context.bob_account, context.charlie_account));
+
+    // once charlie registers, deposits are accepted
+    context.register_recipient(context.charlie_account);
+    assert!(context.is_registered(context.bob_account, context.charlie_account));
+    context.deposit(
+        context.alice_account,
+        Key::Account(context.charlie_account),
+        U512::from(10000000000000u64),
+        None,
+    );
+    context.collect(context.charlie_account);
+
+    let account_balances = context.get_all_accounts_balance();
+    assert_eq!(account_balances.0, U512::from(34000000000000_u64));
+    assert_eq!(account_balances.2, U512::from(57000000000000_u64));
+}
+
+#[test]
+fn test_collect_to_purse() {
+    let mut context = PaymentContract::deploy();
+
+    context.deposit(
+        context.alice_account,
+        Key::Account(context.charlie_account),
+        U512::from(10000000000000u64),
+        None,
+    );
+
+    // charlie moves part of the deposit into a savings purse, their main purse only pays for the deploy
+    context.collect_to_purse(
+        context.charlie_account,
+        "savings",
+        Some(U512::from(4000000000000u64)),
+        None,
+    );
+    assert_eq!(
+        context.get_named_purse_balance(&context.charlie_account, "savings"),
+        U512::from(4000000000000u64)
+    );
+    let account_balances = context.get_all_accounts_balance();
+    assert_eq!(account_balances.2, U512::from(48500000000000_u64));
+
+    // the rest is collected into the same, already existing purse
+    context.collect_to_purse(context.charlie_account, "savings", None, None);
+    assert_eq!(
+        context.get_named_purse_balance(&context.charlie_account, "savings"),
+        U512::from(10000000000000u64)
+    );
+    let account_balances = context.get_all_accounts_balance();
+    assert_eq!(account_balances.2, U512::from(47000000000000_u64));
+}
+
+#[test]
+fn test_collect_to_target_and_withdrawal_address() {
+    const DAY: u64 = 24 * 60 * 60 * 1000;
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+
+    context.deposit(
+        alice,
+        Key::Account(charlie),
+        U512::from(10000000000000u64),
+        None,
+    );
+
+    // charlie collects part of the deposit straight to bob
+    context.collect_with(
+        charlie,
+        Some(U512::from(2000000000000u64)),
+        Some(bob),
+        None,
+        None,
+        None,
+    );
+
+    // the first withdrawal address takes effect immediately and locks every other destination
+    context.call(
+        charlie,
+        "set_withdrawal_address",
+        runtime_args! {"withdrawal_address" => Some(bob)},
+    );
+    context.call_expect_error(
+        charlie,
+        "collect",
+        runtime_args! {
+            "amount" => Option::<U512>::None,
+            "target" => Some(alice),
+            "id" => Option::<u64>::None,
+            "tag" => Option::<u64>::None
+        },
+        Error::WithdrawalAddressLocked,
+    );
+    context.session_expect_error(
+        charlie,
+        "collect_session.wasm",
+        runtime_args! {
+            "deposit_contract_hash" => context.contract_hash,
+            "purse_name" => "savings".to_string(),
+            "amount" => Option::<U512>::None,
+            "id" => Option::<u64>::None,
+            "tag" => Option::<u64>::None
+        },
+        Error::WithdrawalAddressLocked,
+    );
+    context.collect_with(
+        charlie,
+        Some(U512::from(2000000000000u64)),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    // changing the address only takes effect a day later
+    context.call_at(
+        charlie,
+        "set_withdrawal_address",
+        runtime_args! {"withdrawal_address" => Some(charlie)},
+        1000,
+    );
+    context.collect_with(
+        charlie,
+        Some(U512::from(2000000000000u64)),
+        None,
+        None,
+        None,
+        Some(2000),
+    );
+    context.collect_with(charlie, None, None, None, None, Some(1000 + DAY));
+
+    let account_balances = context.get_all_accounts_balance();
+    assert_eq!(account_balances.1, U512::from(56000000000000_u64));
+    assert_eq!(account_balances.2, U512::from(42000000000000_u64));
+}
+
+#[test]
+fn test_transfer_ids() {
+    let mut context = PaymentContract::deploy();
+    let amount = U512::from(10000000000000u64);
+
+    // both transfers of "deposit_session" carry the id, the last one moving the motes into the contract
+    context.deposit(
+        context.alice_account,
+        Key::Account(context.charlie_account),
+        amount,
+        Some(7),
+    );
+    let transfers = last_transfers(&context.builder);
+    assert_eq!(transfers.len(), 2);
+    assert!(transfers
+        .iter()
+        .all(|transfer| transfer.id == Some(7) && transfer.amount == amount));
+
+    // "deposit_into_session" transfers straight into the deposit purse
+    context.deposit_into(
+        context.bob_account,
+        Key::Account(context.charlie_account),
+        amount,
+        Some(8),
+    );
+    let transfers = last_transfers(&context.builder);
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].id, Some(8));
+    assert_eq!(transfers[0].from, context.bob_account);
+
+    // the payout of `collect` carries the id passed in by charlie
+    context.collect_with(context.charlie_account, None, None, Some(9), None, None);
+    let transfers = last_transfers(&context.builder);
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].id, Some(9));
+    assert_eq!(transfers[0].to, Some(context.charlie_account));
+    assert_eq!(transfers[0].amount, amount + amount);
+}
+
+#[test]
+fn test_destination_tags() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+
+    // charlie receives deposits of two customers under separate tags, and an untagged one
+    context.deposit_tagged(
+        alice,
+        Key::Account(charlie),
+        U512::from(10000000000000u64),
+        1,
+    );
+    context.deposit_tagged(bob, Key::Account(charlie), U512::from(5000000000000u64), 2);
+    context.deposit_into(
+        alice,
+        Key::Account(charlie),
+        U512::from(3000000000000u64),
+        None,
+    );
+    // `get_deposit_balance` reports the balance of a tag, or of the whole deposit without one
+    let balance_args =
+        |tag: Option<u64>| runtime_args! {"recipient" => Key::Account(charlie), "tag" => tag};
+    for (tag, balance) in [
+        (Some(1u64), 10000000000000u64),
+        (Some(2), 5000000000000),
+        (Some(4), 0),
+        (None, 18000000000000),
+    ] {
+        assert_eq!(
+            context.query_entry_point::<U512>(alice, "get_deposit_balance", balance_args(tag)),
+            U512::from(balance)
+        );
+    }
+
+    // collecting under a tag is limited to the balance of that tag
+    context.collect_with(
+        charlie,
+        Some(U512::from(4000000000000u64)),
+        None,
+        None,
+        Some(1),
+        None,
+    );
+    context.call_expect_error(
+        charlie,
+        "collect",
+        runtime_args! {
+            "amount" => Some(U512::from(7000000000000u64)),
+            "target" => Option::<AccountHash>::None,
+            "id" => Option::<u64>::None,
+            "tag" => Some(1u64)
+        },
+        Error::InsufficientBalance,
+    );
+    context.collect_with(charlie, None, None, None, Some(1), None);
+    context.collect_with(charlie, None, None, None, Some(2), None);
+
+    // collecting without a tag only reaches the untagged motes, tagged ones need their tag
+    context.deposit_tagged(bob, Key::Account(charlie), U512::from(4000000000000u64), 3);
+    context.call_expect_error(
+        charlie,
+        "collect",
+        runtime_args! {
+            "amount" => Some(U512::from(5000000000000u64)),
+            "target" => Option::<AccountHash>::None,
+            "id" => Option::<u64>::None,
+            "tag" => Option::<u64>::None
+        },
+        Error::InsufficientBalance,
+    );
+    context.collect_with(charlie, None, None, None, None, None);
+    for (tag, balance) in [(Some(3u64), 4000000000000u64), (None, 4000000000000)] {
+        assert_eq!(
+            context.query_entry_point::<U512>(alice, "get_deposit_balance", balance_args(tag)),
+            U512::from(balance)
+        );
+    }
+    context.collect_with(charlie, None, None, None, Some(3), None);
+
+    let account_balances = context.get_all_accounts_balance();
+    assert_eq!(account_balances.2, U512::from(61500000000000_u64));
+}
+
+#[test]
+fn test_escrow_release_refund_and_dispute() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    // alice funds their own deposit, which the escrows are paid out of
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(20000000000000u64),
+        None,
+    );
+
+    // escrow 0 is released by the payer, and only by the payer
+    context.open_escrow(alice, bob, charlie, U512::from(5000000000000u64));
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(15000000000000u64)
+    );
+    context.call_expect_error(
+        bob,
+        "release_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        Error::NotPayer,
+    );
+    context.escrow_call(alice, "release_escrow", 0);
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(5000000000000u64)
+    );
+    context.call_expect_error(
+        alice,
+        "release_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        Error::EscrowNotOpen,
+    );
+
+    // escrow 1 is refunded by the payee
+    context.open_escrow(alice, bob, charlie, U512::from(3000000000000u64));
+    context.escrow_call(bob, "refund_escrow", 1);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(15000000000000u64)
+    );
+
+    // escrow 2 is disputed, after which only the arbiter can move the motes
+    context.open_escrow(alice, bob, charlie, U512::from(4000000000000u64));
+    context.escrow_call(bob, "dispute_escrow", 2);
+    context.call_expect_error(
+        alice,
+        "release_escrow",
+        runtime_args! {"escrow_id" => 2u64},
+        Error::EscrowNotOpen,
+    );
+    context.call_expect_error(
+        bob,
+        "settle_escrow",
+        runtime_args! {"escrow_id" => 2u64, "payee_amount" => U512::zero()},
+        Error::NotArbiter,
+    );
+    context.call_expect_error(
+        charlie,
+        "settle_escrow",
+        runtime_args! {"escrow_id" => 2u64, "payee_amount" => U512::from(5000000000000u64)},
+        Error::SettlementExceedsEscrow,
+    );
+    context.call(
+        charlie,
+        "settle_escrow",
+        runtime_args! {"escrow_id" => 2u64, "payee_amount" => U512::from(1000000000000u64)},
+    );
+    context.call_expect_error(
+        charlie,
+        "settle_escrow",
+        runtime_args! {"escrow_id" => 2u64, "payee_amount" => U512::zero()},
+        Error::EscrowNotDisputed,
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(14000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(6000000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("escrow_purse"),
+        U512::zero()
+    );
+
+    // the arbiter has to be a third party, and unknown escrows cannot be touched
+    context.call_expect_error(
+        alice,
+        "open_escrow",
+        runtime_args! {
+            "payee" => bob,
+            "arbiter" => bob,
+            "amount" => U512::from(1000000000000u64)
+        },
+        Error::InvalidArbiter,
+    );
+    context.call_expect_error(
+        charlie,
+        "dispute_escrow",
+        runtime_args! {"escrow_id" => 2u64},
+        Error::EscrowNotOpen,
+    );
+    context.call_expect_error(
+        alice,
+        "release_escrow",
+        runtime_args! {"escrow_id" => 9u64},
+        Error::EscrowNotFound,
+    );
+}
+
+#[test]
+fn test_milestone_escrow() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob) = (context.alice_account, context.bob_account);
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(20000000000000u64),
+        None,
+    );
+
+    // an escrow without milestones, or with one for nothing, is rejected
+    for milestones in [vec![], vec![U512::from(2000000000000u64), U512::zero()]] {
+        context.call_expect_error(
+            alice,
+            "open_milestone_escrow",
+            runtime_args! {"payee" => bob, "milestones" => milestones},
+            Error::InvalidMilestones,
+        );
+    }
+    context.call(
+        alice,
+        "open_milestone_escrow",
+        runtime_args! {
+            "payee" => bob,
+            "milestones" => vec![
+                U512::from(2000000000000u64),
+                U512::from(3000000000000u64),
+                U512::from(5000000000000u64)
+            ]
+        },
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(10000000000000u64)
+    );
+
+    context.milestone_call(alice, "approve_milestone", 0, 0);
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(2000000000000u64)
+    );
+    context.call_expect_error(
+        bob,
+        "approve_milestone",
+        runtime_args! {"escrow_id" => 0u64, "milestone" => 1u32},
+        Error::NotPayer,
+    );
+    context.milestone_call(alice, "cancel_milestone", 0, 1);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(13000000000000u64)
+    );
+    context.call_expect_error(
+        alice,
+        "approve_milestone",
+        runtime_args! {"escrow_id" => 0u64, "milestone" => 1u32},
+        Error::MilestoneNotPending,
+    );
+    context.call_expect_error(
+        alice,
+        "approve_milestone",
+        runtime_args! {"escrow_id" => 0u64, "milestone" => 3u32},
+        Error::MilestoneNotFound,
+    );
+    context.milestone_call(alice, "approve_milestone", 0, 2);
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(7000000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("escrow_purse"),
+        U512::zero()
+    );
+}
+
+#[test]
+fn test_optimistic_escrow() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    // alice installed the contract, so they resolve the disputes between charlie and bob
+    context.deposit(
+        charlie,
+        Key::Account(charlie),
+        U512::from(20000000000000u64),
+        None,
+    );
+    let open_args = |amount: u64| {
+        runtime_args! {
+            "payee" => bob,
+            "amount" => U512::from(amount),
+            "challenge_window" => 1000u64
+        }
+    };
+
+    // escrow 0 is claimed by bob once the window has passed
+    context.call_at(
+        charlie,
+        "open_optimistic_escrow",
+        open_args(5000000000000),
+        1000,
+    );
+    // only the payer can dispute an optimistic escrow
+    context.call_expect_error_at(
+        bob,
+        "dispute_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        Error::NotPayer,
+        Some(1500),
+    );
+    context.call_expect_error_at(
+        bob,
+        "claim_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        Error::EscrowNotClaimable,
+        Some(1999),
+    );
+    context.call_expect_error_at(
+        charlie,
+        "claim_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        Error::NotPayee,
+        Some(2000),
+    );
+    context.call_at(
+        bob,
+        "claim_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        2000,
+    );
+    context.call_expect_error_at(
+        bob,
+        "claim_escrow",
+        runtime_args! {"escrow_id" => 0u64},
+        Error::EscrowNotOpen,
+        Some(2000),
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(5000000000000u64)
+    );
+
+    // escrow 1 cannot be disputed late, and is claimed by bob
+    context.call_at(
+        charlie,
+        "open_optimistic_escrow",
+        open_args(3000000000000),
+        1000,
+    );
+    context.call_expect_error_at(
+        charlie,
+        "dispute_escrow",
+        runtime_args! {"escrow_id" => 1u64},
+        Error::ChallengeWindowClosed,
+        Some(2000),
+    );
+    context.call_at(
+        bob,
+        "claim_escrow",
+        runtime_args! {"escrow_id" => 1u64},
+        2000,
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(8000000000000u64)
+    );
+
+    // escrow 2 is disputed in time, stays frozen past the window and is resolved by the owner
+    context.call_at(
+        charlie,
+        "open_optimistic_escrow",
+        open_args(4000000000000),
+        1000,
+    );
+    context.call_at(
+        charlie,
+        "dispute_escrow",
+        runtime_args! {"escrow_id" => 2u64},
+        1500,
+    );
+    context.call_expect_error_at(
+        bob,
+        "claim_escrow",
+        runtime_args! {"escrow_id" => 2u64},
+        Error::EscrowNotOpen,
+        Some(5000),
+    );
+    context.call_at(
+        alice,
+        "settle_escrow",
+        runtime_args! {"escrow_id" => 2u64, "payee_amount" => U512::from(1000000000000u64)},
+        5000,
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(9000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(11000000000000u64)
+    );
+}
+
+#[test]
+fn test_subscriptions() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    // bob and charlie subscribe to alice, who charges them once per 1000ms
+    context.deposit(bob, Key::Account(bob), U512::from(20000000000000u64), None);
+    context.deposit(
+        charlie,
+        Key::Account(charlie),
+        U512::from(6000000000000u64),
+        None,
+    );
+    let subscribe_args = |amount: u64, funding: u64| {
+        runtime_args! {
+            "merchant" => alice,
+            "amount" => U512::from(amount),
+            "period" => 1000u64,
+            "funding" => U512::from(funding)
+        }
+    };
+    let merchant_subscriptions = |context: &mut PaymentContract| {
+        context.query_entry_point::<Vec<u64>>(
+            alice,
+            "get_merchant_subscriptions",
+            runtime_args! {"merchant" => alice},
+        )
+    };
+    // a subscription charging nothing would never end
+    context.call_expect_error(
+        bob,
+        "subscribe",
+        subscribe_args(0, 5000000000000),
+        Error::InvalidSubscriptionAmount,
+    );
+    context.call(
+        bob,
+        "subscribe",
+        subscribe_args(1000000000000, 5000000000000),
+    );
+    context.call_expect_error(
+        charlie,
+        "subscribe",
+        subscribe_args(2000000000000, 1000000000000),
+        Error::InsufficientBalance,
+    );
+    context.call(
+        charlie,
+        "subscribe",
+        subscribe_args(2000000000000, 5000000000000),
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(15000000000000u64)
+    );
+    assert_eq!(merchant_subscriptions(&mut context), vec![0, 1]);
+
+    // once per period, and a period that was not charged in is skipped
+    let charge = runtime_args! {"subscription_id" => 0u64};
+    context.call_at(alice, "charge", charge.clone(), 0);
+    context.call_expect_error_at(
+        alice,
+        "charge",
+        charge.clone(),
+        Error::ChargeNotDue,
+        Some(999),
+    );
+    context.call_expect_error_at(
+        charlie,
+        "charge",
+        charge.clone(),
+        Error::NotMerchant,
+        Some(1000),
+    );
+    context.call_at(alice, "charge", charge.clone(), 1500);
+    context.call_at(alice, "charge", charge.clone(), 3500);
+    context.call_expect_error_at(
+        alice,
+        "charge",
+        charge.clone(),
+        Error::ChargeNotDue,
+        Some(3999),
+    );
+    context.call_at(alice, "charge", charge.clone(), 4000);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(4000000000000u64)
+    );
+
+    // cancelling returns the unused balance and takes the subscription off the merchants list
+    context.call_expect_error_at(
+        charlie,
+        "cancel_subscription",
+        charge.clone(),
+        Error::NotSubscriber,
+        Some(4500),
+    );
+    context.call_at(bob, "cancel_subscription", charge.clone(), 4500);
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(16000000000000u64)
+    );
+    context.call_expect_error_at(
+        alice,
+        "charge",
+        charge,
+        Error::SubscriptionCancelled,
+        Some(5000),
+    );
+    assert_eq!(merchant_subscriptions(&mut context), vec![1]);
+
+    // charlie funded two and a half periods, so the second charge ends the subscription
+    // and credits the remaining half back
+    let charge = runtime_args! {"subscription_id" => 1u64};
+    context.call_at(alice, "charge", charge.clone(), 5000);
+    context.call_at(alice, "charge", charge.clone(), 6000);
+    context.call_expect_error_at(
+        alice,
+        "charge",
+        charge,
+        Error::SubscriptionCancelled,
+        Some(7000),
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(8000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(2000000000000u64)
+    );
+    assert_eq!(merchant_subscriptions(&mut context), Vec::<u64>::new());
+}
+
+#[test]
+fn test_scheduled_payments() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let keeper_tip = runtime_args! {"keeper_tip" => U512::from(100000000000u64)};
+    context.call_expect_error(bob, "set_keeper_tip", keeper_tip.clone(), Error::NotOwner);
+    context.call(alice, "set_keeper_tip", keeper_tip);
+
+    // alice schedules three payments to bob, each locking the keeper tip on top
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(20000000000000u64),
+        None,
+    );
+    for (amount, execute_after) in [
+        (5000000000000u64, 1000u64),
+        (3000000000000, 2000),
+        (1000000000000, 1000),
+    ] {
+        context.call(
+            alice,
+            "schedule_payment",
+            runtime_args! {
+                "recipient" => Key::Account(bob),
+                "amount" => U512::from(amount),
+                "execute_after" => execute_after
+            },
+        );
+    }
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(10700000000000u64)
+    );
+
+    // the payer can cancel until the payment is executed
+    context.call_expect_error_at(
+        charlie,
+        "execute_due",
+        runtime_args! {"payment_ids" => vec![0u64, 1]},
+        Error::NoPaymentDue,
+        Some(999),
+    );
+    context.call_expect_error(
+        bob,
+        "cancel_payment",
+        runtime_args! {"payment_id" => 2u64},
+        Error::NotPayer,
+    );
+    context.call(
+        alice,
+        "cancel_payment",
+        runtime_args! {"payment_id" => 2u64},
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(11800000000000u64)
+    );
+    context.call_expect_error(
+        alice,
+        "cancel_payment",
+        runtime_args! {"payment_id" => 2u64},
+        Error::PaymentNotScheduled,
+    );
+
+    // any account can execute due payments, skipping the ones that are not due, cancelled or unknown
+    context.call_at(
+        charlie,
+        "execute_due",
+        runtime_args! {"payment_ids" => vec![0u64, 1, 2, 7]},
+        1500,
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(5000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(100000000000u64)
+    );
+
+    // while registration is required, scheduling checks that the recipient is registered,
+    // and executing registers the keeper, so an unregistered one still gets the tip
+    let dave = context.fund_new_account(4);
+    context.call_at(
+        alice,
+        "set_registration_required",
+        runtime_args! {"registration_required" => true},
+        1500,
+    );
+    context.call_expect_error_at(
+        alice,
+        "schedule_payment",
+        runtime_args! {
+            "recipient" => Key::Account(AccountHash::new([7u8; 32])),
+            "amount" => U512::from(1000000000000u64),
+            "execute_after" => 3000u64
+        },
+        Error::RecipientNotRegistered,
+        Some(1500),
+    );
+    context.call_at(
+        dave,
+        "execute_due",
+        runtime_args! {"payment_ids" => vec![1u64]},
+        2000,
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(8000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(dave),
+        U512::from(100000000000u64)
+    );
+    context.call_expect_error_at(
+        alice,
+        "cancel_payment",
+        runtime_args! {"payment_id" => 1u64},
+        Error::PaymentNotScheduled,
+        Some(2000),
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("scheduled_payment_purse"),
+        U512::zero()
+    );
+}
+
+#[test]
+fn test_merkle_airdrop() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    // five leaves, so the last one is carried up a layer without a sibling
+    let leaves = [
+        (alice, U512::from(1000000000000u64)),
+        (bob, U512::from(2000000000000u64)),
+        (charlie, U512::from(3000000000000u64)),
+        (AccountHash::new([8u8; 32]), U512::from(4000000000000u64)),
+        (AccountHash::new([9u8; 32]), U512::from(5000000000000u64)),
+    ];
+    let tree = MerkleTree::new(&leaves);
+    let claim_args = |index: usize, amount: U512| {
+        runtime_args! {
+            "distribution_id" => 0u64,
+            "amount" => amount,
+            "proof" => tree.proof(index)
+        }
+    };
+
+    // alice installed the contract, so they fund the distribution out of their deposit
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(15000000000000u64),
+        None,
+    );
+    let fund_args = runtime_args! {
+        "merkle_root" => tree.root(),
+        "amount" => U512::from(15000000000000u64),
+        "deadline" => 1000u64
+    };
+    context.call_expect_error(bob, "fund_distribution", fund_args.clone(), Error::NotOwner);
+    context.call(alice, "fund_distribution", fund_args);
+    assert_eq!(context.get_deposit_balance(alice), U512::zero());
+
+    // every account claims its own leaf exactly once
+    context.call(bob, "claim", claim_args(1, leaves[1].1));
+    assert_eq!(context.get_deposit_balance(bob), leaves[1].1);
+    context.call_expect_error(
+        bob,
+        "claim",
+        claim_args(1, leaves[1].1),
+        Error::AlreadyClaimed,
+    );
+    context.call_expect_error(
+        charlie,
+        "claim",
+        claim_args(2, leaves[3].1),
+        Error::InvalidProof,
+    );
+    context.call_expect_error(
+        charlie,
+        "claim",
+        claim_args(1, leaves[1].1),
+        Error::InvalidProof,
+    );
+    context.call(charlie, "claim", claim_args(2, leaves[2].1));
+    assert_eq!(context.get_deposit_balance(charlie), leaves[2].1);
+    assert_eq!(
+        context.get_contract_purse_balance("distribution_purse"),
+        U512::from(10000000000000u64)
+    );
+
+    // claims close at the deadline, after which the funder reclaims what is left
+    context.call_expect_error_at(
+        alice,
+        "claim",
+        claim_args(0, leaves[0].1),
+        Error::DistributionExpired,
+        Some(1000),
+    );
+    let reclaim = runtime_args! {"distribution_id" => 0u64};
+    context.call_expect_error_at(
+        alice,
+        "reclaim_distribution",
+        reclaim.clone(),
+        Error::DistributionNotExpired,
+        Some(999),
+    );
+    context.call_expect_error_at(
+        bob,
+        "reclaim_distribution",
+        reclaim.clone(),
+        Error::NotFunder,
+        Some(1000),
+    );
+    context.call_at(alice, "reclaim_distribution", reclaim, 1000);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(10000000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("distribution_purse"),
+        U512::zero()
+    );
+
+    context.call_expect_error_at(
+        alice,
+        "claim",
+        runtime_args! {
+            "distribution_id" => 1u64,
+            "amount" => leaves[0].1,
+            "proof" => tree.proof(0)
+        },
+        Error::DistributionNotFound,
+        Some(1000),
+    );
+}
+
+#[test]
+fn test_payword_channel() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    // chain[k] is the preimage k links deep, chain[0] is the tip
+    let mut chain = vec![[7u8; 32]];
+    for _ in 0..12 {
+        let next = crypto::blake2b(chain.last().unwrap());
+        chain.push(next);
+    }
+    chain.reverse();
+    let redeem_args = |links: u64| {
+        runtime_args! {
+            "channel_id" => 0u64,
+            "preimage" => chain[links as usize],
+            "links" => links
+        }
+    };
+
+    // alice locks ten links of 0.1 CSPR each for bob
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(1000000000000u64),
+        None,
+    );
+    let open_args = |link_value: u64| {
+        runtime_args! {
+            "payee" => bob,
+            "chain_tip" => chain[0],
+            "link_value" => U512::from(link_value),
+            "amount" => U512::from(1000000000000u64),
+            "timeout" => 1000u64
+        }
+    };
+    // links worth nothing would let bob hash through a chain of any length
+    context.call_expect_error(alice, "open_channel", open_args(0), Error::InvalidLinkValue);
+    context.call(alice, "open_channel", open_args(100000000000));
+
+    // bob redeems the deepest preimage they got, and only the links they were not paid for yet count
+    context.call_expect_error(charlie, "redeem_channel", redeem_args(3), Error::NotPayee);
+    context.call(bob, "redeem_channel", redeem_args(3));
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(300000000000u64)
+    );
+    context.call(bob, "redeem_channel", redeem_args(5));
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(500000000000u64)
+    );
+    context.call_expect_error(
+        bob,
+        "redeem_channel",
+        redeem_args(5),
+        Error::InvalidPreimage,
+    );
+    context.call_expect_error(
+        bob,
+        "redeem_channel",
+        runtime_args! {"channel_id" => 0u64, "preimage" => chain[6], "links" => 7u64},
+        Error::InvalidPreimage,
+    );
+    context.call_expect_error(
+        bob,
+        "redeem_channel",
+        redeem_args(11),
+        Error::InsufficientBalance,
+    );
+
+    // after the timeout bob is too late, and alice gets the rest back
+    context.call_expect_error(
+        alice,
+        "reclaim_channel",
+        runtime_args! {"channel_id" => 0u64},
+        Error::ChannelNotExpired,
+    );
+    context.call_expect_error_at(
+        bob,
+        "redeem_channel",
+        redeem_args(6),
+        Error::ChannelExpired,
+        Some(1000),
+    );
+    context.call_at(
+        alice,
+        "reclaim_channel",
+        runtime_args! {"channel_id" => 0u64},
+        1000,
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(500000000000u64)
+    );
+    context.call_expect_error_at(
+        alice,
+        "reclaim_channel",
+        runtime_args! {"channel_id" => 0u64},
+        Error::ChannelClosed,
+        Some(1000),
+    );
+}
+
+#[test]
+fn test_signed_cheques() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let alice_secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let charlie_secret_key = SecretKey::ed25519_from_bytes([3u8; 32]).unwrap();
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(10000000000000u64),
+        None,
+    );
+    context.call(
+        alice,
+        "register_cheque_key",
+        runtime_args! {"public_key" => PublicKey::from(&alice_secret_key)},
+    );
+    let redeem_args = |payer: AccountHash, amount: u64, nonce: u64, signature: [u8; 64]| {
+        runtime_args! {
+            "payer" => payer,
+            "amount" => U512::from(amount),
+            "nonce" => nonce,
+            "expiry" => 1000u64,
+            "signature" => signature
+        }
+    };
+
+    // bob redeems a cheque of alice exactly once
+    let cheque = context.sign_cheque(
+        &alice_secret_key,
+        bob,
+        U512::from(2000000000000u64),
+        1,
+        1000,
+    );
+    context.call_at(
+        bob,
+        "redeem_cheque",
+        redeem_args(alice, 2000000000000, 1, cheque),
+        500,
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(2000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(8000000000000u64)
+    );
+    context.call_expect_error_at(
+        bob,
+        "redeem_cheque",
+        redeem_args(alice, 2000000000000, 1, cheque),
+        Error::ChequeAlreadyRedeemed,
+        Some(500),
+    );
+
+    // the signature covers the recipient and the amount
+    let cheque = context.sign_cheque(
+        &alice_secret_key,
+        bob,
+        U512::from(2000000000000u64),
+        2,
+        1000,
+    );
+    context.call_expect_error(
+        charlie,
+        "redeem_cheque",
+        redeem_args(alice, 2000000000000, 2, cheque),
+        Error::InvalidSignature,
+    );
+    context.call_expect_error(
+        bob,
+        "redeem_cheque",
+        redeem_args(alice, 3000000000000, 2, cheque),
+        Error::InvalidSignature,
+    );
+    context.call_expect_error_at(
+        bob,
+        "redeem_cheque",
+        redeem_args(alice, 2000000000000, 2, cheque),
+        Error::ChequeExpired,
+        Some(1000),
+    );
+
+    // charlie never registered a key, and the contract hash the cheques are bound to cannot be replaced
+    let cheque = context.sign_cheque(
+        &charlie_secret_key,
+        bob,
+        U512::from(1000000000000u64),
+        1,
+        1000,
+    );
+    context.call_expect_error(
+        bob,
+        "redeem_cheque",
+        redeem_args(charlie, 1000000000000, 1, cheque),
+        Error::NoChequeKey,
+    );
+    let contract_hash = context.contract_hash;
+    context.call_expect_error(
+        alice,
+        "init",
+        runtime_args! {"deposit_contract_hash" => contract_hash},
+        Error::AlreadyInitialized,
+    );
+}
+
+#[test]
+fn test_crowdfunding_campaigns() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    // charlie raises 10 CSPR twice, both campaigns end at block time 1000
+    for _ in 0..2 {
+        context.call(
+            charlie,
+            "create_campaign",
+            runtime_args! {
+                "goal" => U512::from(10000000000000u64),
+                "deadline" => 1000u64,
+                "beneficiary" => charlie
+            },
+        );
+    }
+
+    // campaign 0 meets its goal and is finalized by anyone
+    context.contribute(alice, 0, U512::from(6000000000000u64));
+    context.contribute(bob, 0, U512::from(5000000000000u64));
+    context.call(
+        bob,
+        "finalize_campaign",
+        runtime_args! {"campaign_id" => 0u64},
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(11000000000000u64)
+    );
+    context.session_expect_error(
+        alice,
+        "contribute_session.wasm",
+        runtime_args! {
+            "deposit_contract_hash" => context.contract_hash,
+            "campaign_id" => 0u64,
+            "amount" => U512::from(1000000000000u64)
+        },
+        Error::CampaignEnded,
+    );
+    context.call_expect_error_at(
+        alice,
+        "refund_contribution",
+        runtime_args! {"campaign_id" => 0u64},
+        Error::CampaignNotFailed,
+        Some(1000),
+    );
+
+    // campaign 1 falls short, and every contributor gets back what they put in after the deadline
+    context.contribute(alice, 1, U512::from(3000000000000u64));
+    context.contribute(alice, 1, U512::from(3000000000000u64));
+    context.contribute(bob, 1, U512::from(2000000000000u64));
+    let campaign = runtime_args! {"campaign_id" => 1u64};
+    context.call_expect_error(
+        alice,
+        "finalize_campaign",
+        campaign.clone(),
+        Error::GoalNotMet,
+    );
+    context.call_expect_error(
+        alice,
+        "refund_contribution",
+        campaign.clone(),
+        Error::CampaignNotFailed,
+    );
+    context.call_at(alice, "refund_contribution", campaign.clone(), 1000);
+    context.call_at(bob, "refund_contribution", campaign.clone(), 1000);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(6000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(2000000000000u64)
+    );
+    context.call_expect_error_at(
+        alice,
+        "refund_contribution",
+        campaign.clone(),
+        Error::NothingToRefund,
+        Some(1000),
+    );
+    context.call_expect_error_at(
+        charlie,
+        "refund_contribution",
+        campaign,
+        Error::NothingToRefund,
+        Some(1000),
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("campaign_purse"),
+        U512::zero()
+    );
+}
+
+#[test]
+fn test_bill_splitting() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(5000000000000u64),
+        None,
+    );
+    context.deposit(bob, Key::Account(bob), U512::from(5000000000000u64), None);
+    let share = U512::from(1000000000000u64);
+    for shares in [
+        vec![],
+        vec![(alice, share), (bob, share), (alice, share)],
+        vec![(alice, share), (bob, U512::zero())],
+    ] {
+        context.call_expect_error(
+            charlie,
+            "create_bill",
+            runtime_args! {
+                "payee" => charlie,
+                "shares" => shares,
+                "expires_at" => 1000u64
+            },
+            Error::InvalidBill,
+        );
+    }
+
+    // alice and bob split three bills to charlie, all expiring at block time 1000
+    for _ in 0..3 {
+        context.call(
+            charlie,
+            "create_bill",
+            runtime_args! {
+                "payee" => charlie,
+                "shares" => vec![
+                    (alice, U512::from(2000000000000u64)),
+                    (bob, U512::from(3000000000000u64))
+                ],
+                "expires_at" => 1000u64
+            },
+        );
+    }
+    let bill = |bill_id: u64| runtime_args! {"bill_id" => bill_id};
+
+    // bill 0 is paid in full and collected by charlie
+    context.call_expect_error(charlie, "collect_bill", bill(0), Error::BillNotFullyPaid);
+    context.call(alice, "pay_bill_share", bill(0));
+    context.call_expect_error(alice, "pay_bill_share", bill(0), Error::ShareAlreadyPaid);
+    context.call_expect_error(charlie, "pay_bill_share", bill(0), Error::NotParticipant);
+    context.call(bob, "pay_bill_share", bill(0));
+    context.call_expect_error(bob, "collect_bill", bill(0), Error::NotPayee);
+    context.call(charlie, "collect_bill", bill(0));
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(5000000000000u64)
+    );
+    context.call_expect_error(
+        alice,
+        "withdraw_bill_share",
+        bill(0),
+        Error::BillNotRefundable,
+    );
+
+    // bill 1 expires before bob pays, so alice withdraws their share
+    context.call(alice, "pay_bill_share", bill(1));
+    context.call_expect_error_at(
+        bob,
+        "pay_bill_share",
+        bill(1),
+        Error::BillExpired,
+        Some(1000),
+    );
+    context.call_expect_error_at(
+        alice,
+        "withdraw_bill_share",
+        bill(1),
+        Error::BillNotRefundable,
+        Some(999),
+    );
+    context.call_expect_error_at(
+        bob,
+        "withdraw_bill_share",
+        bill(1),
+        Error::NothingToRefund,
+        Some(1000),
+    );
+    context.call_at(alice, "withdraw_bill_share", bill(1), 1000);
+
+    // bill 2 is cancelled by charlie
+    context.call(alice, "pay_bill_share", bill(2));
+    context.call_expect_error(alice, "cancel_bill", bill(2), Error::NotPayee);
+    context.call(charlie, "cancel_bill", bill(2));
+    context.call_expect_error(bob, "pay_bill_share", bill(2), Error::BillNotOpen);
+    context.call(alice, "withdraw_bill_share", bill(2));
+
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(3000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(2000000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("bill_purse"),
+        U512::zero()
+    );
+}
+
+#[test]
+fn test_savings_circle() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let dave = context.fund_new_account(4);
+    let erin = context.fund_new_account(5);
+    let members = vec![alice, bob, charlie, dave, erin];
+    for member in &members {
+        context.deposit(
+            *member,
+            Key::Account(*member),
+            U512::from(5000000000000u64),
+            None,
+        );
+    }
+    let circle_args = |members: Vec<AccountHash>, contribution: u64, round_length: u64| {
+        runtime_args! {
+            "members" => members,
+            "contribution" => U512::from(contribution),
+            "penalty" => U512::from(100000000000u64),
+            "round_length" => round_length
+        }
+    };
+    // a member listed twice, a contribution of nothing, or rounds that are overdue as soon as they start
+    for (circle_members, contribution, round_length) in [
+        (vec![alice, bob, alice], 1000000000000u64, 1000u64),
+        (members.clone(), 0, 1000),
+        (members.clone(), 1000000000000, 0),
+    ] {
+        context.call_expect_error(
+            charlie,
+            "create_circle",
+            circle_args(circle_members, contribution, round_length),
+            Error::InvalidCircle,
+        );
+    }
+    // everyone pays 1T per round of 1000ms, or 1.1T once the round is overdue
+    context.call(
+        charlie,
+        "create_circle",
+        circle_args(members.clone(), 1000000000000, 1000),
+    );
+    let circle = runtime_args! {"circle_id" => 0u64};
+    let frank = context.fund_new_account(6);
+    context.call_expect_error(frank, "pay_round", circle.clone(), Error::NotMember);
+
+    // round 0: everyone pays on time, and alice receives the pot
+    for member in &members {
+        context.call_at(*member, "pay_round", circle.clone(), 500);
+    }
+    context.call_expect_error_at(
+        alice,
+        "pay_round",
+        circle.clone(),
+        Error::RoundAlreadyPaid,
+        Some(500),
+    );
+    context.call_at(bob, "payout_round", circle.clone(), 500);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(9000000000000u64)
+    );
+    context.call_expect_error(
+        alice,
+        "exit_circle",
+        circle.clone(),
+        Error::PotAlreadyReceived,
+    );
+
+    // round 1: erin pays late, and bob receives the pot including their penalty
+    for member in &[alice, bob, charlie, dave] {
+        context.call_at(*member, "pay_round", circle.clone(), 1500);
+    }
+    context.call_expect_error_at(
+        bob,
+        "payout_round",
+        circle.clone(),
+        Error::RoundNotFullyPaid,
+        Some(1500),
+    );
+    context.call_at(erin, "pay_round", circle.clone(), 2000);
+    context.call_at(alice, "payout_round", circle.clone(), 2000);
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(6100000000000u64)
+    );
+
+    // round 2: dave pays and then exits, getting back only the 1T they paid into this round,
+    // so charlie receives the contributions of the other four
+    context.call_at(dave, "pay_round", circle.clone(), 2100);
+    context.call_at(dave, "exit_circle", circle.clone(), 2200);
+    context.call_expect_error_at(
+        dave,
+        "pay_round",
+        circle.clone(),
+        Error::MemberExited,
+        Some(2200),
+    );
+    for member in &[alice, bob, charlie, erin] {
+        context.call_at(*member, "pay_round", circle.clone(), 2500);
+    }
+    context.call_at(charlie, "payout_round", circle.clone(), 2500);
+
+    // round 3: bob never pays, so once the round is overdue frank marks them as defaulted,
+    // and erin receives the last pot, as dave gave up their turn
+    for member in &[alice, charlie, erin] {
+        context.call_at(*member, "pay_round", circle.clone(), 3500);
+    }
+    let defaulted = |member: AccountHash| runtime_args! {"circle_id" => 0u64, "member" => member};
+    context.call_expect_error_at(
+        frank,
+        "mark_defaulted",
+        defaulted(bob),
+        Error::RoundNotOverdue,
+        Some(3900),
+    );
+    context.call_expect_error_at(
+        frank,
+        "mark_defaulted",
+        defaulted(alice),
+        Error::RoundAlreadyPaid,
+        Some(4000),
+    );
+    context.call_expect_error_at(
+        frank,
+        "mark_defaulted",
+        defaulted(dave),
+        Error::MemberExited,
+        Some(4000),
+    );
+    context.call_expect_error_at(
+        frank,
+        "payout_round",
+        circle.clone(),
+        Error::RoundNotFullyPaid,
+        Some(4000),
+    );
+    context.call_at(frank, "mark_defaulted", defaulted(bob), 4000);
+    context.call_at(erin, "payout_round", circle.clone(), 4000);
+    context.call_expect_error_at(
+        alice,
+        "pay_round",
+        circle,
+        Error::CircleFinished,
+        Some(4500),
+    );
+
+    for (member, balance) in [
+        (alice, 6000000000000u64),
+        (bob, 7100000000000u64),
+        (charlie, 5000000000000u64),
+        (dave, 3000000000000u64),
+        (erin, 3900000000000u64),
+    ] {
+        assert_eq!(context.get_deposit_balance(member), U512::from(balance));
+    }
+    assert_eq!(
+        context.get_contract_purse_balance("circle_purse"),
+        U512::zero()
+    );
+
+    // the pots are collected like any other deposit
+    context.collect(erin);
+    assert_eq!(context.get_deposit_balance(erin), U512::zero());
+    assert_eq!(
+        context.get_balance(&erin),
+        U512::from(45000000000000u64) + U512::from(3900000000000u64)
+            - U512::from(1500000000000u64) * 7
+    );
+}
+
+#[test]
+fn test_bounty_board() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(5000000000000u64),
+        None,
+    );
+    // alice posts two bounties, both open for claims until block time 1000
+    for amount in [2000000000000u64, 1000000000000u64] {
+        context.call(
+            alice,
+            "post_bounty",
+            runtime_args! {
+                "description_hash" => [7u8; 32],
+                "amount" => U512::from(amount),
+                "expiry" => 1000u64
+            },
+        );
+    }
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(2000000000000u64)
+    );
+    let bounty = |bounty_id: u64| runtime_args! {"bounty_id" => bounty_id};
+    let award = |bounty_id: u64, claimant: AccountHash| {
+        runtime_args! {"bounty_id" => bounty_id, "claimant" => claimant}
+    };
+    context.call_expect_error(bob, "refund_bounty", bounty(2), Error::BountyNotFound);
+    context.call_expect_error(
+        alice,
+        "submit_claim",
+        runtime_args! {"bounty_id" => 0u64, "evidence_hash" => [3u8; 32]},
+        Error::PosterCannotClaim,
+    );
+
+    // bounty 0 is claimed by bob and charlie, and awarded to bob
+    for (hunter, evidence_hash) in [(bob, [1u8; 32]), (charlie, [2u8; 32])] {
+        context.call(
+            hunter,
+            "submit_claim",
+            runtime_args! {"bounty_id" => 0u64, "evidence_hash" => evidence_hash},
+        );
+    }
+    assert_eq!(
+        context.get_dictionary_value::<[u8; 32]>("bounty_claims", dictionary_key(&(0u64, charlie))),
+        Some([2u8; 32])
+    );
+    context.call_expect_error(charlie, "award", award(0, charlie), Error::NotPoster);
+    context.call_expect_error(alice, "award", award(1, bob), Error::ClaimNotFound);
+    context.call(alice, "award", award(0, bob));
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(2000000000000u64)
+    );
+    context.call_expect_error(alice, "award", award(0, charlie), Error::BountyClosed);
+    context.call_expect_error_at(
+        alice,
+        "refund_bounty",
+        bounty(0),
+        Error::BountyClosed,
+        Some(1000),
+    );
+
+    // bounty 1 expires without claims, and alice takes the reward back
+    context.call_expect_error_at(
+        bob,
+        "submit_claim",
+        runtime_args! {"bounty_id" => 1u64, "evidence_hash" => [1u8; 32]},
+        Error::BountyExpired,
+        Some(1000),
+    );
+    context.call_expect_error_at(
+        alice,
+        "refund_bounty",
+        bounty(1),
+        Error::BountyNotExpired,
+        Some(999),
+    );
+    context.call_at(alice, "refund_bounty", bounty(1), 1000);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(3000000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("bounty_purse"),
+        U512::zero()
+    );
+}
+
+#[test]
+fn test_donation_matching_pools() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    for sponsor in [alice, bob] {
+        context.deposit(
+            sponsor,
+            Key::Account(sponsor),
+            U512::from(5000000000000u64),
+            None,
+        );
+    }
+    // alice matches the donations to charlie one to one up to 3T, and bob at half up to 1T
+    for (sponsor, ratio, amount) in [
+        (alice, 10000u32, 3000000000000u64),
+        (bob, 5000, 1000000000000),
+    ] {
+        context.call(
+            sponsor,
+            "fund_matching_pool",
+            runtime_args! {
+                "charity" => charlie,
+                "ratio" => ratio,
+                "amount" => U512::from(amount)
+            },
+        );
+    }
+    let pool_amounts = |context: &mut PaymentContract, pool_id: u64| {
+        let pool = runtime_args! {"matching_pool_id" => pool_id};
+        (
+            context.query_entry_point::<U512>(charlie, "get_matching_pool_remaining", pool.clone()),
+            context.query_entry_point::<U512>(charlie, "get_total_matched", pool),
+        )
+    };
+    for ratio in [0u32, 100001] {
+        context.call_expect_error(
+            bob,
+            "fund_matching_pool",
+            runtime_args! {
+                "charity" => charlie,
+                "ratio" => ratio,
+                "amount" => U512::from(1000000000000u64)
+            },
+            Error::InvalidRatio,
+        );
+    }
+
+    // 2T is matched with 2T from alice's pool and all of the 1T in bob's pool
+    context.deposit(
+        bob,
+        Key::Account(charlie),
+        U512::from(2000000000000u64),
+        None,
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(5000000000000u64)
+    );
+    assert_eq!(
+        pool_amounts(&mut context, 1),
+        (U512::zero(), U512::from(1000000000000u64))
+    );
+
+    // alice's pool runs out partway through the next 2T, matching only the 1T left
+    context.deposit(
+        alice,
+        Key::Account(charlie),
+        U512::from(2000000000000u64),
+        None,
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(8000000000000u64)
+    );
+    assert_eq!(
+        pool_amounts(&mut context, 0),
+        (U512::zero(), U512::from(3000000000000u64))
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("matching_purse"),
+        U512::zero()
+    );
+
+    // without pools left, donations are no longer matched
+    context.deposit(
+        bob,
+        Key::Account(charlie),
+        U512::from(1000000000000u64),
+        None,
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(9000000000000u64)
+    );
+
+    // closing a pool credits what is left in it back to the sponsor
+    context.call(
+        alice,
+        "fund_matching_pool",
+        runtime_args! {
+            "charity" => charlie,
+            "ratio" => 10000u32,
+            "amount" => U512::from(1000000000000u64)
+        },
+    );
+    // charlie donating to themselves is not matched
+    context.deposit(
+        charlie,
+        Key::Account(charlie),
+        U512::from(1000000000000u64),
+        None,
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(10000000000000u64)
+    );
+    assert_eq!(
+        pool_amounts(&mut context, 2),
+        (U512::from(1000000000000u64), U512::zero())
+    );
+    let pool = runtime_args! {"matching_pool_id" => 2u64};
+    context.call_expect_error(bob, "close_matching_pool", pool.clone(), Error::NotSponsor);
+    context.call(alice, "close_matching_pool", pool);
+    context.call_expect_error(
+        alice,
+        "close_matching_pool",
+        runtime_args! {"matching_pool_id" => 3u64},
+        Error::MatchingPoolNotFound,
+    );
+    assert_eq!(pool_amounts(&mut context, 2), (U512::zero(), U512::zero()));
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(2000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(4000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(10000000000000u64)
+    );
+
+    // pools hold at least 100 CSPR, and a donation too small to be matched leaves them in place
+    let small_pool = |amount: u64| {
+        runtime_args! {
+            "charity" => charlie,
+            "ratio" => 1u32,
+            "amount" => U512::from(amount)
+        }
+    };
+    context.call_expect_error(
+        alice,
+        "fund_matching_pool",
+        small_pool(99999999999),
+        Error::MatchingPoolTooSmall,
+    );
+    context.call(alice, "fund_matching_pool", small_pool(100000000000));
+    context.deposit(bob, Key::Account(charlie), U512::from(5000u64), None);
+    assert_eq!(
+        pool_amounts(&mut context, 3),
+        (U512::from(100000000000u64), U512::zero())
+    );
+    context.deposit(
+        bob,
+        Key::Account(charlie),
+        U512::from(2000000000000u64),
+        None,
+    );
+    assert_eq!(
+        pool_amounts(&mut context, 3),
+        (U512::from(99800000000u64), U512::from(200000000u64))
+    );
+
+    // a charity has at most 10 pools
+    for _ in 0..9 {
+        context.call(alice, "fund_matching_pool", small_pool(100000000000));
+    }
+    context.call_expect_error(
+        alice,
+        "fund_matching_pool",
+        small_pool(100000000000),
+        Error::TooManyMatchingPools,
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(1000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(charlie),
+        U512::from(12000200005000u64)
+    );
+}
+
+#[test]
+fn test_dead_man_switch() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    context.deposit(
+        alice,
+        Key::Account(bob),
+        U512::from(10000000000000u64),
+        None,
+    );
+    context.call_expect_error(alice, "heartbeat", runtime_args! {}, Error::NoBeneficiary);
+    let day = 24 * 60 * 60 * 1000u64;
+    let beneficiary = |inactivity_period: u64| {
+        runtime_args! {"beneficiary" => Some(charlie), "inactivity_period" => inactivity_period}
+    };
+    context.call_expect_error(
+        bob,
+        "set_beneficiary",
+        beneficiary(day - 1),
+        Error::InvalidInactivityPeriod,
+    );
+    // bob names charlie, who can claim their deposit after a day without activity
+    context.call(bob, "set_beneficiary", beneficiary(day));
+    let claim = runtime_args! {"recipient" => bob};
+    context.call_expect_error(
+        alice,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::NotBeneficiary,
+    );
+    context.call_expect_error_at(
+        charlie,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::RecipientStillActive,
+        Some(day - 1),
+    );
+
+    // collecting at 500, the heartbeat a day later and posting a bounty out of the deposit each reset the timer
+    context.collect_with(
+        bob,
+        Some(U512::from(1000000000000u64)),
+        None,
+        None,
+        None,
+        Some(500),
+    );
+    context.call_expect_error_at(
+        charlie,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::RecipientStillActive,
+        Some(day),
+    );
+    context.call_at(bob, "heartbeat", runtime_args! {}, day + 400);
+    context.call_expect_error_at(
+        charlie,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::RecipientStillActive,
+        Some(2 * day + 399),
+    );
+    context.call_at(
+        bob,
+        "post_bounty",
+        runtime_args! {
+            "description_hash" => [7u8; 32],
+            "amount" => U512::from(1000000000000u64),
+            "expiry" => 3 * day
+        },
+        2 * day,
+    );
+    context.call_expect_error_at(
+        charlie,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::RecipientStillActive,
+        Some(3 * day - 1),
+    );
+
+    // charlie claims the rest of bob's deposit once bob has been inactive for a day
+    let charlie_balance = context.get_balance(&charlie);
+    context.call_at(charlie, "claim_inactive_deposit", claim, 3 * day);
+    assert_eq!(
+        context.get_balance(&charlie),
+        charlie_balance + U512::from(8000000000000u64) - U512::from(1500000000000u64)
+    );
+    assert_eq!(context.get_deposit_balance(bob), U512::zero());
+
+    // a period as long as `u64::MAX` never runs out
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(1000000000000u64),
+        None,
+    );
+    context.call_at(alice, "set_beneficiary", beneficiary(u64::MAX), 3 * day);
+    context.call_expect_error_at(
+        charlie,
+        "claim_inactive_deposit",
+        runtime_args! {"recipient" => alice},
+        Error::RecipientStillActive,
+        Some(4 * day),
+    );
+}
+
+#[test]
+fn test_guardian_recovery() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let dave = context.fund_new_account(4);
+    let new_account = AccountHash::new([9u8; 32]);
+    context.deposit(
+        alice,
+        Key::Account(bob),
+        U512::from(10000000000000u64),
+        None,
+    );
+    // any 2 of alice, charlie and dave can move bob's deposit, 1000ms after they agreed
+    let guardians = |threshold: u32| {
+        runtime_args! {
+            "guardians" => vec![alice, charlie, dave],
+            "threshold" => threshold,
+            "recovery_delay" => 1000u64
+        }
+    };
+    context.call_expect_error(bob, "set_guardians", guardians(4), Error::InvalidThreshold);
+    context.call_expect_error(bob, "set_guardians", guardians(0), Error::InvalidThreshold);
+    context.call_expect_error(
+        bob,
+        "set_guardians",
+        runtime_args! {
+            "guardians" => vec![alice, charlie, charlie],
+            "threshold" => 3u32,
+            "recovery_delay" => 1000u64
+        },
+        Error::DuplicateGuardian,
+    );
+    context.call(bob, "set_guardians", guardians(2));
+    let approve = runtime_args! {"recipient" => bob, "new_account" => new_account};
+    let execute = runtime_args! {"recipient" => bob};
+
+    // the first attempt is vetoed by bob during the delay
+    context.call_expect_error(bob, "approve_recovery", approve.clone(), Error::NotGuardian);
+    context.call(alice, "approve_recovery", approve.clone());
+    context.call_expect_error(
+        alice,
+        "approve_recovery",
+        approve.clone(),
+        Error::AlreadyApproved,
+    );
+    context.call_expect_error(
+        charlie,
+        "execute_recovery",
+        execute.clone(),
+        Error::NoRecoveryPending,
+    );
+    context.call_at(charlie, "approve_recovery", approve.clone(), 100);
+    context.call_expect_error_at(
+        dave,
+        "approve_recovery",
+        approve.clone(),
+        Error::RecoveryPending,
+        Some(100),
+    );
+    context.call_expect_error_at(
+        charlie,
+        "execute_recovery",
+        execute.clone(),
+        Error::RecoveryNotReady,
+        Some(1099),
+    );
+    context.call_at(bob, "veto_recovery", runtime_args! {}, 500);
+    context.call_expect_error_at(
+        charlie,
+        "execute_recovery",
+        execute.clone(),
+        Error::NoRecoveryPending,
+        Some(1100),
+    );
+    context.call_expect_error(
+        bob,
+        "veto_recovery",
+        runtime_args! {},
+        Error::NoRecoveryPending,
+    );
+
+    // the veto discarded the earlier approvals, so alice approves again along with dave
+    context.call_at(alice, "approve_recovery", approve.clone(), 1200);
+    context.call_at(dave, "approve_recovery", approve, 1200);
+    context.call_at(charlie, "execute_recovery", execute, 2200);
+    assert_eq!(context.get_deposit_balance(bob), U512::zero());
+    assert_eq!(
+        context.get_deposit_balance(new_account),
+        U512::from(10000000000000u64)
+    );
+
+    // charlie pinned their deposit to bob and put it into a vault, so a recovery waits out the vault delay
+    // and pays bob, where charlie can still reach their funds
+    context.deposit(
+        alice,
+        Key::Account(charlie),
+        U512::from(4000000000000u64),
+        None,
+    );
+    context.call(
+        charlie,
+        "set_guardians",
+        runtime_args! {
+            "guardians" => vec![alice],
+            "threshold" => 1u32,
+            "recovery_delay" => 0u64
+        },
+    );
+    context.call(
+        charlie,
+        "set_withdrawal_address",
+        runtime_args! {"withdrawal_address" => Some(bob)},
+    );
+    context.call(
+        charlie,
+        "set_vault_delay",
+        runtime_args! {"withdrawal_delay" => 1000u64},
+    );
+    context.call_at(
+        alice,
+        "approve_recovery",
+        runtime_args! {"recipient" => charlie, "new_account" => new_account},
+        3000,
+    );
+    let execute = runtime_args! {"recipient" => charlie};
+    context.call_expect_error_at(
+        dave,
+        "execute_recovery",
+        execute.clone(),
+        Error::RecoveryNotReady,
+        Some(3999),
+    );
+    let bob_balance = context.get_balance(&bob);
+    context.call_at(dave, "execute_recovery", execute, 4000);
+    assert_eq!(context.get_deposit_balance(charlie), U512::zero());
+    assert_eq!(
+        context.get_balance(&bob),
+        bob_balance + U512::from(4000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(new_account),
+        U512::from(10000000000000u64)
+    );
+}
+
+#[test]
+fn test_vault_withdrawals() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    context.deposit(
+        alice,
+        Key::Account(bob),
+        U512::from(10000000000000u64),
+        None,
+    );
+    let request = |amount: u64| runtime_args! {"amount" => U512::from(amount)};
+    let cancel = runtime_args! {"recipient" => bob};
+    let collect = runtime_args! {
+        "amount" => Option::<U512>::None,
+        "target" => Option::<AccountHash>::None,
+        "id" => Option::<u64>::None,
+        "tag" => Option::<u64>::None
+    };
+    context.call_expect_error(
+        bob,
+        "request_withdrawal",
+        request(1000000000000),
+        Error::VaultNotEnabled,
+    );
+    // bob registers charlie as a guardian, and puts their deposit into a vault with a delay of 1000ms
+    context.call(
+        bob,
+        "set_guardians",
+        runtime_args! {
+            "guardians" => vec![charlie],
+            "threshold" => 1u32,
+            "recovery_delay" => 1000u64
+        },
+    );
+    context.call(
+        bob,
+        "set_vault_delay",
+        runtime_args! {"withdrawal_delay" => 1000u64},
+    );
+    context.call_expect_error(bob, "collect", collect.clone(), Error::VaultLocked);
+
+    // the first withdrawal is cancelled by charlie
+    context.call(bob, "request_withdrawal", request(2000000000000));
+    context.call_expect_error(
+        bob,
+        "request_withdrawal",
+        request(1000000000000),
+        Error::WithdrawalPending,
+    );
+    context.call_expect_error_at(
+        bob,
+        "finalize_withdrawal",
+        runtime_args! {},
+        Error::WithdrawalNotReady,
+        Some(999),
+    );
+    context.call_expect_error(
+        alice,
+        "cancel_withdrawal",
+        cancel.clone(),
+        Error::NotGuardian,
+    );
+    context.call_at(charlie, "cancel_withdrawal", cancel, 100);
+    context.call_expect_error_at(
+        bob,
+        "finalize_withdrawal",
+        runtime_args! {},
+        Error::NoWithdrawalPending,
+        Some(1000),
+    );
+
+    // the second one is finalized once the delay has passed
+    context.call_at(bob, "request_withdrawal", request(3000000000000), 200);
+    let bob_balance = context.get_balance(&bob);
+    context.call_at(bob, "finalize_withdrawal", runtime_args! {}, 1200);
+    assert_eq!(
+        context.get_balance(&bob),
+        bob_balance + U512::from(3000000000000u64) - U512::from(1500000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(7000000000000u64)
+    );
+
+    // turning the vault off only takes effect after the current delay
+    context.call_at(
+        bob,
+        "set_vault_delay",
+        runtime_args! {"withdrawal_delay" => 0u64},
+        1300,
+    );
+    context.call_expect_error_at(
+        bob,
+        "collect",
+        collect.clone(),
+        Error::VaultLocked,
+        Some(2299),
+    );
+    context.collect_with(bob, None, None, None, None, Some(2300));
+    assert_eq!(context.get_deposit_balance(bob), U512::zero());
+
+    // a delay of `u64::MAX` locks the vault for good, neither withdrawals nor turning it off ever come due
+    context.deposit(alice, Key::Account(bob), U512::from(1000000000000u64), None);
+    context.call_at(
+        bob,
+        "set_vault_delay",
+        runtime_args! {"withdrawal_delay" => u64::MAX},
+        2400,
+    );
+    context.call_at(bob, "request_withdrawal", request(1000000000000), 2400);
+    context.call_at(
+        bob,
+        "set_vault_delay",
+        runtime_args! {"withdrawal_delay" => 0u64},
+        2400,
+    );
+    context.call_expect_error_at(
+        bob,
+        "finalize_withdrawal",
+        runtime_args! {},
+        Error::WithdrawalNotReady,
+        Some(5000),
+    );
+    context.call_expect_error_at(bob, "collect", collect, Error::VaultLocked, Some(5000));
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(1000000000000u64)
+    );
+}
+
+#[test]
+fn test_vault_cannot_be_bypassed() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let dave = context.fund_new_account(4);
+    let new_account = AccountHash::new([9u8; 32]);
+    context.deposit(
+        alice,
+        Key::Account(bob),
+        U512::from(10000000000000u64),
+        None,
+    );
+    let day = 24 * 60 * 60 * 1000u64;
+    let switch = |beneficiary: AccountHash| {
+        runtime_args! {"beneficiary" => Some(beneficiary), "inactivity_period" => day}
+    };
+    let guardians = |guardian: AccountHash| {
+        runtime_args! {
+            "guardians" => vec![guardian],
+            "threshold" => 1u32,
+            "recovery_delay" => 0u64
+        }
+    };
+    let approve = runtime_args! {"recipient" => bob, "new_account" => new_account};
+    let execute = runtime_args! {"recipient" => bob};
+    let claim = runtime_args! {"recipient" => bob};
+    // bob names charlie as beneficiary and guardian, then puts their deposit into a vault with a delay of 1000ms
+    context.call(bob, "set_beneficiary", switch(charlie));
+    context.call(bob, "set_guardians", guardians(charlie));
+    context.call(
+        bob,
+        "set_vault_delay",
+        runtime_args! {"withdrawal_delay" => 1000u64},
+    );
+
+    // an approved recovery waits out the vault delay, even with a recovery delay of 0
+    context.call(charlie, "approve_recovery", approve.clone());
+    context.call_expect_error_at(
+        charlie,
+        "execute_recovery",
+        execute.clone(),
+        Error::RecoveryNotReady,
+        Some(999),
+    );
+
+    // a stolen key naming dave as beneficiary and guardian only takes effect after the vault delay
+    context.call_at(bob, "set_beneficiary", switch(dave), 100);
+    context.call_at(bob, "set_guardians", guardians(dave), 100);
+    context.call_expect_error_at(
+        dave,
+        "approve_recovery",
+        approve.clone(),
+        Error::NotGuardian,
+        Some(1099),
+    );
+    context.call_expect_error_at(
+        dave,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::NotBeneficiary,
+        Some(1099),
+    );
+    context.call_at(dave, "approve_recovery", approve, 1100);
+    context.call_expect_error_at(
+        dave,
+        "execute_recovery",
+        execute.clone(),
+        Error::RecoveryNotReady,
+        Some(2099),
+    );
+    // which leaves bob the vault delay to veto the recovery
+    context.call_at(bob, "veto_recovery", runtime_args! {}, 1150);
+    context.call_expect_error_at(
+        dave,
+        "execute_recovery",
+        execute,
+        Error::NoRecoveryPending,
+        Some(2100),
+    );
+
+    // finalized withdrawals count against the daily limit
+    context.call_at(
+        bob,
+        "set_daily_limit",
+        runtime_args! {"daily_limit" => Some(U512::from(2000000000000u64))},
+        1200,
+    );
+    let request = |amount: u64| runtime_args! {"amount" => U512::from(amount)};
+    context.call_at(bob, "request_withdrawal", request(3000000000000), 1200);
+    context.call_expect_error_at(
+        bob,
+        "finalize_withdrawal",
+        runtime_args! {},
+        Error::DailyLimitExceeded,
+        Some(2200),
+    );
+    context.call_at(
+        bob,
+        "cancel_withdrawal",
+        runtime_args! {"recipient" => bob},
+        2200,
+    );
+    context.call_at(bob, "request_withdrawal", request(2000000000000), 2200);
+    context.call_at(bob, "finalize_withdrawal", runtime_args! {}, 3200);
+    context.call_at(bob, "request_withdrawal", request(1000000000000), 3200);
+    context.call_expect_error_at(
+        bob,
+        "finalize_withdrawal",
+        runtime_args! {},
+        Error::DailyLimitExceeded,
+        Some(4200),
+    );
+
+    // and as activity, so dave has to wait a day from the withdrawal, only to find the deposit still locked
+    context.call_expect_error_at(
+        dave,
+        "claim_inactive_deposit",
+        claim.clone(),
+        Error::RecipientStillActive,
+        Some(3199 + day),
+    );
+    context.call_expect_error_at(
+        dave,
+        "claim_inactive_deposit",
+        claim,
+        Error::VaultLocked,
+        Some(3200 + day),
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(8000000000000u64)
+    );
+}
+
+#[test]
+fn test_daily_withdrawal_limits() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    context.deposit(
+        alice,
+        Key::Account(bob),
+        U512::from(10000000000000u64),
+        None,
+    );
+    let day = 24 * 60 * 60 * 1000u64;
+    let collect = |amount: u64| {
+        runtime_args! {
+            "amount" => Some(U512::from(amount)),
+            "target" => Option::<AccountHash>::None,
+            "id" => Option::<u64>::None,
+            "tag" => Option::<u64>::None
+        }
+    };
+    let collect_at = |context: &mut PaymentContract, amount: u64, block_time: u64| {
+        context.collect_with(
+            bob,
+            Some(U512::from(amount)),
+            None,
+            None,
+            None,
+            Some(block_time),
+        )
+    };
+    let allowance_at = |context: &mut PaymentContract, block_time: u64| {
+        context.query_entry_point_at::<Option<U512>>(
+            alice,
+            "get_remaining_allowance",
+            runtime_args! {"recipient" => bob},
+            Some(block_time),
+        )
+    };
+    assert_eq!(allowance_at(&mut context, 0), None);
+    // bob limits themselves to 3T within any 24 hours, which takes effect right away
+    context.call(
+        bob,
+        "set_daily_limit",
+        runtime_args! {"daily_limit" => Some(U512::from(3000000000000u64))},
+    );
+    collect_at(&mut context, 2000000000000, 0);
+    assert_eq!(
+        allowance_at(&mut context, 500),
+        Some(U512::from(1000000000000u64))
+    );
+    context.call_expect_error_at(
+        bob,
+        "collect",
+        collect(2000000000000),
+        Error::DailyLimitExceeded,
+        Some(1000),
+    );
+    collect_at(&mut context, 1000000000000, 1000);
+    context.call_expect_error_at(
+        bob,
+        "collect",
+        collect(1000000000000),
+        Error::DailyLimitExceeded,
+        Some(day - 1),
+    );
+    assert_eq!(allowance_at(&mut context, day - 1), Some(U512::zero()));
+
+    // the window rolls, so the 2T collected at 0 no longer count a day later
+    assert_eq!(
+        allowance_at(&mut context, day),
+        Some(U512::from(2000000000000u64))
+    );
+    collect_at(&mut context, 2000000000000, day);
+
+    // raising the limit to 5T only takes effect after a day
+    context.call_at(
+        bob,
+        "set_daily_limit",
+        runtime_args! {"daily_limit" => Some(U512::from(5000000000000u64))},
+        day,
+    );
+    context.call_expect_error_at(
+        bob,
+        "collect",
+        collect(1000000000000),
+        Error::DailyLimitExceeded,
+        Some(day + 500),
+    );
+    collect_at(&mut context, 4000000000000, 2 * day);
+
+    // lowering it takes effect immediately
+    context.call_at(
+        bob,
+        "set_daily_limit",
+        runtime_args! {"daily_limit" => Some(U512::from(4500000000000u64))},
+        2 * day,
+    );
+    context.call_expect_error_at(
+        bob,
+        "collect",
+        collect(1000000000000),
+        Error::DailyLimitExceeded,
+        Some(2 * day + 1),
+    );
+    assert_eq!(
+        context.get_deposit_balance(bob),
+        U512::from(1000000000000u64)
+    );
+
+    // payment flows funded out of the deposit count against the limit as well
+    context.call_expect_error_at(
+        bob,
+        "open_escrow",
+        runtime_args! {
+            "payee" => charlie,
+            "arbiter" => alice,
+            "amount" => U512::from(1000000000000u64)
+        },
+        Error::DailyLimitExceeded,
+        Some(2 * day + 1),
+    );
+
+    // with a limit set, a new beneficiary or new guardians only take effect after a day
+    context.call_at(
+        bob,
+        "set_daily_limit",
+        runtime_args! {"daily_limit" => Some(U512::from(500000000000u64))},
+        2 * day + 2,
+    );
+    context.call_at(
+        bob,
+        "set_beneficiary",
+        runtime_args! {"beneficiary" => Some(charlie), "inactivity_period" => day},
+        2 * day + 2,
+    );
+    context.call_at(
+        bob,
+        "set_guardians",
+        runtime_args! {
+            "guardians" => vec![charlie],
+            "threshold" => 1u32,
+            "recovery_delay" => 0u64
+        },
+        2 * day + 2,
+    );
+    let new_account = AccountHash::new([9u8; 32]);
+    let approve = runtime_args! {"recipient" => bob, "new_account" => new_account};
+    let execute = runtime_args! {"recipient" => bob};
+    context.call_expect_error_at(
+        charlie,
+        "claim_inactive_deposit",
+        runtime_args! {"recipient" => bob},
+        Error::NotBeneficiary,
+        Some(3 * day + 1),
+    );
+    context.call_expect_error_at(
+        charlie,
+        "approve_recovery",
+        approve.clone(),
+        Error::NotGuardian,
+        Some(3 * day + 1),
+    );
+
+    // a recovery only moves what is left of the allowance, and stays pending until the deposit is empty
+    context.call_at(charlie, "approve_recovery", approve, 3 * day + 2);
+    context.call_at(charlie, "execute_recovery", execute.clone(), 3 * day + 2);
+    assert_eq!(
+        context.get_deposit_balance(new_account),
+        U512::from(500000000000u64)
+    );
+    context.call_expect_error_at(
+        charlie,
+        "execute_recovery",
+        execute.clone(),
+        Error::DailyLimitExceeded,
+        Some(4 * day + 1),
+    );
+    context.call_at(charlie, "execute_recovery", execute.clone(), 4 * day + 2);
+    context.call_expect_error_at(
+        charlie,
+        "execute_recovery",
+        execute,
+        Error::NoRecoveryPending,
+        Some(4 * day + 2),
+    );
+    assert_eq!(context.get_deposit_balance(bob), U512::zero());
+    assert_eq!(
+        context.get_deposit_balance(new_account),
+        U512::from(1000000000000u64)
+    );
+}
+
+#[test]
+fn test_joint_accounts() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let dave = context.fund_new_account(4);
+    // joint accounts get the account hash of the blake2b hash of their id
+    let joint_account = |id: u64| {
+        AccountHash::new(crypto::blake2b(
+            ("joint_accounts".to_string(), id).to_bytes().unwrap(),
+        ))
+    };
+    let (any_of, two_of) = (joint_account(0), joint_account(1));
+    context.call(
+        charlie,
+        "create_joint_account",
+        runtime_args! {"owners" => vec![alice, bob], "threshold" => 1u32},
+    );
+    context.call(
+        alice,
+        "create_joint_account",
+        runtime_args! {"owners" => vec![alice, bob, charlie], "threshold" => 2u32},
+    );
+    context.call_expect_error(
+        alice,
+        "create_joint_account",
+        runtime_args! {"owners" => vec![alice, bob], "threshold" => 3u32},
+        Error::InvalidThreshold,
+    );
+    context.call_expect_error(
+        alice,
+        "create_joint_account",
+        runtime_args! {"owners" => vec![alice, bob, alice], "threshold" => 3u32},
+        Error::DuplicateOwner,
+    );
+    context.deposit(
+        charlie,
+        Key::Account(any_of),
+        U512::from(4000000000000u64),
+        None,
+    );
+    context.deposit(
+        alice,
+        Key::Account(two_of),
+        U512::from(6000000000000u64),
+        None,
+    );
+    let collect = |joint_account: AccountHash, amount: u64, target: AccountHash| {
+        runtime_args! {
+            "joint_account" => joint_account,
+            "amount" => U512::from(amount),
+            "target" => target
+        }
+    };
+    let owner_change = |owner: AccountHash, threshold: u32| {
+        runtime_args! {"joint_account" => two_of, "owner" => owner, "threshold" => threshold}
+    };
+    let proposal = |proposal_id: u64| runtime_args! {"proposal_id" => proposal_id};
+
+    // any single owner collects from the first account
+    context.call_expect_error(
+        charlie,
+        "propose_joint_collect",
+        collect(any_of, 1000000000000, charlie),
+        Error::NotJointOwner,
+    );
+    let bob_balance = context.get_balance(&bob);
+    context.call(
+        bob,
+        "propose_joint_collect",
+        collect(any_of, 1000000000000, bob),
+    );
+    assert_eq!(
+        context.get_balance(&bob),
+        bob_balance + U512::from(1000000000000u64) - U512::from(1500000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(any_of),
+        U512::from(3000000000000u64)
+    );
+
+    // collecting from the second one takes two of its owners
+    context.call(
+        alice,
+        "propose_joint_collect",
+        collect(two_of, 2000000000000, dave),
+    );
+    assert_eq!(
+        context.get_deposit_balance(two_of),
+        U512::from(6000000000000u64)
+    );
+    context.call_expect_error(
+        alice,
+        "approve_joint_proposal",
+        proposal(1),
+        Error::AlreadyApproved,
+    );
+    context.call(charlie, "approve_joint_proposal", proposal(1));
+    assert_eq!(
+        context.get_deposit_balance(two_of),
+        U512::from(4000000000000u64)
+    );
+    assert_eq!(context.get_balance(&dave), U512::from(52000000000000u64));
+    context.call_expect_error(
+        bob,
+        "approve_joint_proposal",
+        proposal(1),
+        Error::ProposalExecuted,
+    );
+    context.call_expect_error(
+        bob,
+        "approve_joint_proposal",
+        proposal(9),
+        Error::ProposalNotFound,
+    );
+
+    // removing charlie outdates the proposals made before
+    context.call(alice, "propose_remove_owner", owner_change(charlie, 2));
+    context.call(
+        bob,
+        "propose_joint_collect",
+        collect(two_of, 1000000000000, bob),
+    );
+    context.call(bob, "approve_joint_proposal", proposal(2));
+    context.call_expect_error(
+        alice,
+        "approve_joint_proposal",
+        proposal(3),
+        Error::ProposalOutdated,
+    );
+    context.call_expect_error(
+        charlie,
+        "propose_remove_owner",
+        owner_change(alice, 1),
+        Error::NotJointOwner,
+    );
+
+    // alice and bob cannot keep a threshold above the number of owners, but can add dave
+    context.call(alice, "propose_remove_owner", owner_change(bob, 2));
+    context.call_expect_error(
+        bob,
+        "approve_joint_proposal",
+        proposal(4),
+        Error::InvalidThreshold,
+    );
+    context.call(alice, "propose_add_owner", owner_change(dave, 2));
+    context.call(bob, "approve_joint_proposal", proposal(5));
+    context.call(
+        dave,
+        "propose_joint_collect",
+        collect(two_of, 4000000000000, dave),
+    );
+    context.call(alice, "approve_joint_proposal", proposal(6));
+    assert_eq!(context.get_deposit_balance(two_of), U512::zero());
+
+    // removing dave leaves an account of all of alice and bob, which can still remove bob
+    context.call(alice, "propose_remove_owner", owner_change(dave, 2));
+    context.call(bob, "approve_joint_proposal", proposal(7));
+    context.call(alice, "propose_remove_owner", owner_change(bob, 1));
+    context.call(bob, "approve_joint_proposal", proposal(8));
+    context.call_expect_error(
+        bob,
+        "propose_joint_collect",
+        collect(two_of, 1000000000000, bob),
+        Error::NotJointOwner,
+    );
+    context.deposit(
+        charlie,
+        Key::Account(two_of),
+        U512::from(1000000000000u64),
+        None,
+    );
+    context.call(
+        alice,
+        "propose_joint_collect",
+        collect(two_of, 1000000000000, dave),
+    );
+    assert_eq!(context.get_deposit_balance(two_of), U512::zero());
+}
+
+#[test]
+fn test_dao_treasury() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob, charlie) = (
+        context.alice_account,
+        context.bob_account,
+        context.charlie_account,
+    );
+    let dave = context.fund_new_account(4);
+    let erin = context.fund_new_account(5);
+    let frank = context.fund_new_account(6);
+    let contractor = AccountHash::new([9u8; 32]);
+    // 9 votes in total, at least 5 of which have to be cast on a proposal
+    let members = vec![(alice, 3u64), (bob, 2), (charlie, 2), (dave, 1), (erin, 1)];
+    let create = |quorum: u64| {
+        runtime_args! {
+            "members" => members.clone(),
+            "quorum" => quorum,
+            "voting_period" => 1000u64
+        }
+    };
+    context.call_expect_error(alice, "create_treasury", create(10), Error::InvalidQuorum);
+    context.call_expect_error(alice, "create_treasury", create(0), Error::InvalidQuorum);
+    for invalid_members in [
+        vec![(alice, 3u64), (bob, 2), (alice, 1)],
+        vec![(alice, u64::MAX), (bob, 1)],
+    ] {
+        context.call_expect_error(
+            alice,
+            "create_treasury",
+            runtime_args! {
+                "members" => invalid_members,
+                "quorum" => 1u64,
+                "voting_period" => 1000u64
+            },
+            Error::InvalidMembers,
+        );
+    }
+    context.call(alice, "create_treasury", create(5));
+    let treasury = AccountHash::new(crypto::blake2b(
+        ("treasuries".to_string(), 0u64).to_bytes().unwrap(),
+    ));
+    context.deposit(
+        alice,
+        Key::Account(treasury),
+        U512::from(10000000000000u64),
+        None,
+    );
+    let propose = |memo: &str| {
+        runtime_args! {
+            "treasury" => treasury,
+            "recipient" => contractor,
+            "amount" => U512::from(1000000000000u64),
+            "memo" => memo.to_string()
+        }
+    };
+    context.call_expect_error(
+        frank,
+        "create_spending_proposal",
+        propose("frank"),
+        Error::NotTreasuryMember,
+    );
+    for (proposer, memo) in [
+        (alice, "design"),
+        (bob, "audit"),
+        (charlie, "hosting"),
+        (erin, "swag"),
+    ] {
+        context.call(proposer, "create_spending_proposal", propose(memo));
+    }
+    let vote = |proposal_id: u64, support: bool| {
+        runtime_args! {"proposal_id" => proposal_id, "support" => support}
+    };
+    let execute = |proposal_id: u64| runtime_args! {"proposal_id" => proposal_id};
+
+    // proposal 0 reaches the quorum of 5 exactly, with 4 votes for it and 1 against it
+    context.call(alice, "vote", vote(0, true));
+    context.call(dave, "vote", vote(0, false));
+    context.call(erin, "vote", vote(0, true));
+    context.call_expect_error(erin, "vote", vote(0, false), Error::AlreadyVoted);
+    context.call_expect_error(frank, "vote", vote(0, true), Error::NotTreasuryMember);
+    context.call_expect_error_at(bob, "vote", vote(0, true), Error::VotingClosed, Some(1000));
+    context.call_expect_error_at(
+        frank,
+        "execute_spending_proposal",
+        execute(0),
+        Error::VotingNotEnded,
+        Some(999),
+    );
+    context.call_at(frank, "execute_spending_proposal", execute(0), 1000);
+    context.call_expect_error_at(
+        frank,
+        "execute_spending_proposal",
+        execute(0),
+        Error::ProposalExecuted,
+        Some(1000),
+    );
+
+    // proposal 1 is supported unanimously, but misses the quorum with 4 votes
+    for voter in [bob, charlie] {
+        context.call(voter, "vote", vote(1, true));
+    }
+    // proposal 2 reaches the quorum with 6 votes, but ties
+    for (voter, support) in [(alice, false), (bob, true), (dave, true)] {
+        context.call(voter, "vote", vote(2, support));
+    }
+    // proposal 3 gets all 9 votes, and more against it than for it
+    for (voter, support) in [
+        (alice, true),
+        (bob, false),
+        (charlie, false),
+        (dave, false),
+        (erin, true),
+    ] {
+        context.call(voter, "vote", vote(3, support));
+    }
+    for proposal_id in 1..4 {
+        context.call_expect_error_at(
+            frank,
+            "execute_spending_proposal",
+            execute(proposal_id),
+            Error::ProposalRejected,
+            Some(1000),
+        );
+    }
+
+    assert_eq!(
+        context.get_deposit_balance(treasury),
+        U512::from(9000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(contractor),
+        U512::from(1000000000000u64)
+    );
+}
+
+#[test]
+fn test_savings_goals() {
+    let mut context = PaymentContract::deploy();
+    let (alice, bob) = (context.alice_account, context.bob_account);
+    let charity = AccountHash::new([8u8; 32]);
+    let create = |name: &str,
+                  target_amount: Option<U512>,
+                  unlock_at: Option<u64>,
+                  penalty_recipient: Option<AccountHash>| {
+        runtime_args! {
+            "goal_name" => name.to_string(),
+            "target_amount" => target_amount,
+            "unlock_at" => unlock_at,
+            "penalty_rate" => 1000u32,
+            "penalty_recipient" => penalty_recipient
+        }
+    };
+    let save = |name: &str, amount: u64| {
+        runtime_args! {"goal_name" => name.to_string(), "amount" => U512::from(amount)}
+    };
+    let goal = |name: &str| runtime_args! {"goal_name" => name.to_string()};
+    let goal_balance = |context: &mut PaymentContract, name: &str| {
+        context.query_entry_point::<U512>(
+            alice,
+            "get_goal_balance",
+            runtime_args! {"recipient" => alice, "goal_name" => name.to_string()},
+        )
+    };
+    context.deposit(
+        alice,
+        Key::Account(alice),
+        U512::from(10000000000000u64),
+        None,
+    );
+
+    context.call_expect_error(
+        alice,
+        "create_goal",
+        create("car", None, None, Some(charity)),
+        Error::InvalidGoal,
+    );
+    // a penalty paid to the saver, or of nothing, would make locked goals free to withdraw early
+    context.call_expect_error(
+        alice,
+        "create_goal",
+        create("car", None, Some(1000), Some(alice)),
+        Error::InvalidGoal,
+    );
+    context.call_expect_error(
+        alice,
+        "create_goal",
+        runtime_args! {
+            "goal_name" => "car".to_string(),
+            "target_amount" => Option::<U512>::None,
+            "unlock_at" => Some(1000u64),
+            "penalty_rate" => 0u32,
+            "penalty_recipient" => Some(charity)
+        },
+        Error::InvalidGoal,
+    );
+    let car = create(
+        "car",
+        Some(U512::from(3000000000000u64)),
+        None,
+        Some(charity),
+    );
+    context.call(alice, "create_goal", car.clone());
+    context.call_expect_error(alice, "create_goal", car.clone(), Error::GoalExists);
+    // goals are per saver, so bob can use the same name
+    context.call(bob, "create_goal", car.clone());
+    context.call(
+        alice,
+        "create_goal",
+        create("house", None, Some(1000), None),
+    );
+    context.call_expect_error(alice, "save_to_goal", save("boat", 1), Error::GoalNotFound);
+
+    context.call(alice, "save_to_goal", save("car", 2000000000000));
+    context.call(alice, "save_to_goal", save("house", 4000000000000));
+    assert_eq!(
+        goal_balance(&mut context, "car"),
+        U512::from(2000000000000u64)
+    );
+    assert_eq!(
+        goal_balance(&mut context, "house"),
+        U512::from(4000000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(4000000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("savings_goal_purse"),
+        U512::from(6000000000000u64)
+    );
+
+    // the house goal unlocks at its date, and has no penalty recipient to withdraw early
+    context.call_expect_error_at(
+        alice,
+        "withdraw_goal",
+        goal("house"),
+        Error::GoalLocked,
+        Some(999),
+    );
+    context.call_at(alice, "withdraw_goal", goal("house"), 1000);
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(8000000000000u64)
+    );
+
+    // withdrawing the car goal short of its target costs 10%
+    context.call(alice, "withdraw_goal", goal("car"));
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(9800000000000u64)
+    );
+    assert_eq!(
+        context.get_deposit_balance(charity),
+        U512::from(200000000000u64)
+    );
+    context.call_expect_error(alice, "withdraw_goal", goal("car"), Error::GoalNotFound);
+
+    // the name is free again, and a goal that reached its target is withdrawn in full
+    context.call(alice, "create_goal", car);
+    context.call(alice, "save_to_goal", save("car", 3000000000000));
+    context.call(alice, "withdraw_goal", goal("car"));
+    assert_eq!(
+        context.get_deposit_balance(alice),
+        U512::from(9800000000000u64)
+    );
+    assert_eq!(
+        context.get_contract_purse_balance("savings_goal_purse"),
+        U512::zero()
+    );
+}
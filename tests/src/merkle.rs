@@ -0,0 +1,57 @@
+#![allow(dead_code)]
+use casper_types::{account::AccountHash, bytesrepr::ToBytes, crypto, U512};
+
+/// Off-chain builder for the Merkle trees of airdrop distributions, hashing the same way the contract does:
+/// leaves are the blake2b hash of a 0 byte followed by the serialized (account hash, amount) pair, and inner
+/// nodes the blake2b hash of a 1 byte followed by their two children in ascending order.
+/// A node without a sibling is carried up unchanged.
+pub struct MerkleTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    crypto::blake2b([&[1u8][..], &left[..], &right[..]].concat())
+}
+
+impl MerkleTree {
+    /// Builds the tree over the given leaves, which must not be empty.
+    pub fn new(leaves: &[(AccountHash, U512)]) -> Self {
+        let mut layers = vec![leaves.iter().map(Self::leaf).collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(*a, *b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    /// The leaf hash of an (account hash, amount) pair.
+    pub fn leaf(leaf: &(AccountHash, U512)) -> [u8; 32] {
+        crypto::blake2b([vec![0u8], leaf.to_bytes().expect("should serialize")].concat())
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// The sibling hashes from the leaf at `index` up to the root.
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
@@ -5,10 +5,17 @@ use casper_engine_test_support::{
     DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
     DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT,
 };
-use casper_execution_engine::core::engine_state::ExecuteRequest;
+use casper_execution_engine::core::{
+    engine_state::{Error as EngineStateError, ExecuteRequest},
+    execution::Error as ExecutionError,
+};
 use casper_types::{
-    account::AccountHash, bytesrepr::FromBytes, runtime_args, system::mint, CLTyped, ContractHash,
-    ContractPackageHash, Key, RuntimeArgs, StoredValue, U512,
+    account::AccountHash,
+    bytesrepr::{FromBytes, ToBytes},
+    crypto, runtime_args,
+    system::mint,
+    ApiError, CLTyped, ContractHash, ContractPackageHash, Key, RuntimeArgs, StoredValue, Transfer,
+    U512,
 };
 use rand::Rng;
 
@@ -109,6 +116,40 @@ pub fn deploy(
     .commit();
 }
 
+/// Asserts that the last executed deploy reverted with the given error.
+pub fn expect_error(builder: &InMemoryWasmTestBuilder, error: ApiError) {
+    let actual = builder.get_error().expect("should have an error");
+    let expected = EngineStateError::Exec(ExecutionError::Revert(error));
+    assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+}
+
+/// Returns the Transfer records written by the last executed deploy.
+pub fn last_transfers(builder: &InMemoryWasmTestBuilder) -> Vec<Transfer> {
+    builder
+        .get_last_exec_results()
+        .expect("should have exec results")
+        .iter()
+        .flat_map(|exec_result| exec_result.transfers().clone())
+        .map(|transfer_addr| {
+            match builder
+                .query(None, Key::Transfer(transfer_addr), &[])
+                .expect("should have transfer")
+            {
+                StoredValue::Transfer(transfer) => transfer,
+                _ => panic!("should be a transfer"),
+            }
+        })
+        .collect()
+}
+
+/// Same as `dictionary_key` of the contract, the hex encoded blake2b hash of the serialized value.
+pub fn dictionary_key<T: ToBytes>(value: &T) -> String {
+    crypto::blake2b(value.to_bytes().expect("should serialize"))
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
 pub fn query_dictionary_item(
     builder: &InMemoryWasmTestBuilder,
     key: Key,